@@ -0,0 +1,326 @@
+use crate::cancellation_token::CancellationToken;
+use crate::installation_data::helper_random_name;
+use std::fmt;
+use std::fs;
+use std::io::{Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+pub const BASE_DIR_LOCK_FILE_NAME: &str = ".lifeblood-manager.lock";
+pub const BASE_DIR_GENERATION_FILE_NAME: &str = ".lifeblood-manager.generation";
+
+///
+/// what to do when installation dir is locked by another manager
+///
+#[derive(Debug, Clone, PartialEq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct BaseDirLockSettings {
+    /// wait for the lock to be released instead of failing right away
+    pub wait: bool,
+    /// give up waiting after this many seconds, wait forever if not set
+    pub wait_timeout_secs: Option<u64>,
+}
+
+///
+/// who holds the lock, this is what lock file contains
+///
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+struct LockHolder {
+    pid: u32,
+    command: String,
+    host: String,
+    /// process start time, to tell it from a different process that got the same pid later
+    start_time: u64,
+}
+
+///
+/// advisory lock of the installation dir, released when dropped
+///
+/// it's a file in the installation dir, created atomically with the info about the holder.
+/// lock left by a process that is not running any more is considered stale and is taken over,
+/// but only if that process was on the same host, otherwise there is no way to tell
+///
+pub(crate) struct BaseDirLock {
+    lock_path: PathBuf,
+    held: Arc<AtomicBool>,
+}
+
+impl BaseDirLock {
+    ///
+    /// take the lock of the given installation dir
+    ///
+    /// held is shared by all operations of one InstallationsData, if it's set -
+    /// the operation is nested in another one that already holds the lock, then None is returned
+    ///
+    pub fn acquire(
+        base_path: &Path,
+        settings: &BaseDirLockSettings,
+        held: &Arc<AtomicBool>,
+        cancel: &CancellationToken,
+    ) -> Result<Option<BaseDirLock>, Error> {
+        if held.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        let lock_path = base_path.join(BASE_DIR_LOCK_FILE_NAME);
+        let me = LockHolder::current();
+        let wait_start = Instant::now();
+        let mut waiting_reported = false;
+
+        loop {
+            match Self::try_create(&lock_path, &me) {
+                Ok(()) => {
+                    held.store(true, Ordering::SeqCst);
+                    return Ok(Some(BaseDirLock {
+                        lock_path,
+                        held: held.clone(),
+                    }));
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => (),
+                Err(e) => {
+                    return Err(Error::new(
+                        e.kind(),
+                        format!("failed to create lock file {:?}: {}", lock_path, e),
+                    ));
+                }
+            }
+
+            let holder = match LockHolder::read_from_file(&lock_path) {
+                Ok(x) => x,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue, // just released
+                Err(e) => {
+                    return Err(Error::new(
+                        e.kind(),
+                        format!(
+                            "lock file {:?} is unreadable, remove it if no other manager is running: {}",
+                            lock_path, e
+                        ),
+                    ));
+                }
+            };
+
+            if !holder.is_alive(&me) {
+                println!("taking over stale lock left by {}", holder);
+                Self::remove_stale(&lock_path, &holder, &me)?;
+                continue;
+            }
+
+            let message = format!("installation dir {:?} is locked by {}", base_path, holder);
+            if !settings.wait {
+                return Err(Error::new(ErrorKind::WouldBlock, message));
+            }
+            if let Some(timeout) = settings.wait_timeout_secs {
+                if wait_start.elapsed() >= Duration::from_secs(timeout) {
+                    return Err(Error::new(
+                        ErrorKind::TimedOut,
+                        format!("timed out waiting for lock, {}", message),
+                    ));
+                }
+            }
+            if !waiting_reported {
+                println!("{}, waiting for it to be released", message);
+                waiting_reported = true;
+            }
+            cancel.check()?;
+            sleep(Duration::from_millis(500));
+        }
+    }
+
+    ///
+    /// number of locked operations done in given installation dir so far,
+    /// managers compare it with the one they saw last to tell if someone else changed the dir
+    ///
+    /// missing or unreadable counter counts as 0
+    ///
+    pub fn generation(base_path: &Path) -> u64 {
+        fs::read_to_string(base_path.join(BASE_DIR_GENERATION_FILE_NAME))
+            .ok()
+            .and_then(|x| x.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    ///
+    /// count one more locked operation in the installation dir, returns new generation
+    ///
+    pub fn bump_generation(&self) -> Result<u64, Error> {
+        let generation_path = self.lock_path.with_file_name(BASE_DIR_GENERATION_FILE_NAME);
+        let generation = match self.lock_path.parent() {
+            Some(base_path) => Self::generation(base_path) + 1,
+            None => 1,
+        };
+
+        // replaced atomically, so that it's never seen half written
+        let temp_path = generation_path.with_file_name(format!(
+            "{}.{}",
+            BASE_DIR_GENERATION_FILE_NAME,
+            helper_random_name()
+        ));
+        let result = fs::write(&temp_path, generation.to_string())
+            .and_then(|_| fs::rename(&temp_path, &generation_path));
+        if let Err(e) = result {
+            fs::remove_file(&temp_path).ok();
+            return Err(Error::new(
+                e.kind(),
+                format!("failed to write {:?}: {}", generation_path, e),
+            ));
+        }
+        Ok(generation)
+    }
+
+    ///
+    /// create lock file with holder info, fails with AlreadyExists if it's there
+    ///
+    fn try_create(lock_path: &Path, holder: &LockHolder) -> Result<(), Error> {
+        let contents = match toml::to_string(holder) {
+            Ok(x) => x,
+            Err(e) => return Err(Error::new(ErrorKind::InvalidData, e.to_string())),
+        };
+
+        // lock file appears together with its contents, so it's never seen half written
+        let temp_path = lock_path.with_file_name(format!(
+            "{}.{}",
+            BASE_DIR_LOCK_FILE_NAME,
+            helper_random_name()
+        ));
+        fs::write(&temp_path, &contents)?;
+        let result = fs::hard_link(&temp_path, lock_path);
+        if let Err(e) = fs::remove_file(&temp_path) {
+            eprintln!("failed to remove temporary file {:?}: {}", temp_path, e);
+        }
+
+        match result {
+            Err(e) if e.kind() != ErrorKind::AlreadyExists => {
+                // some filesystems cannot into hard links
+                println!("cannot hard link lock file ({}), creating it directly", e);
+                let mut file = fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(lock_path)?;
+                file.write_all(contents.as_bytes())
+            }
+            x => x,
+        }
+    }
+
+    ///
+    /// remove lock file, but only if it's still the stale one,
+    /// as someone else may have taken over the stale lock in the meantime
+    ///
+    /// several managers may find the same stale lock at once, so only the one
+    /// that managed to create takeover file checks the lock again and removes it.
+    /// while takeover file is there no one else can remove the lock,
+    /// so nothing can happen to it between the check and the removal
+    ///
+    fn remove_stale(
+        lock_path: &Path,
+        stale_holder: &LockHolder,
+        me: &LockHolder,
+    ) -> Result<(), Error> {
+        let takeover_path =
+            lock_path.with_file_name(format!("{}.takeover", BASE_DIR_LOCK_FILE_NAME));
+        match Self::try_create(&takeover_path, me) {
+            Ok(()) => (),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                // someone else is taking over, unless it died doing so
+                match LockHolder::read_from_file(&takeover_path) {
+                    Ok(holder) if !holder.is_alive(me) => {
+                        println!("removing takeover file left by {}", holder);
+                        fs::remove_file(&takeover_path).ok();
+                    }
+                    _ => (),
+                }
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        }
+
+        let result = match LockHolder::read_from_file(lock_path) {
+            Ok(holder) if holder == *stale_holder => fs::remove_file(lock_path),
+            // already taken over and locked again
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = fs::remove_file(&takeover_path) {
+            eprintln!("failed to remove takeover file {:?}: {}", takeover_path, e);
+        }
+        result
+    }
+}
+
+impl Drop for BaseDirLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.lock_path) {
+            eprintln!(
+                "failed to remove lock file {:?}, remove it manually: {}",
+                self.lock_path, e
+            );
+        }
+        self.held.store(false, Ordering::SeqCst);
+    }
+}
+
+impl LockHolder {
+    fn current() -> LockHolder {
+        let pid = std::process::id();
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[Pid::from_u32(pid)]),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        LockHolder {
+            pid,
+            command: std::env::args().collect::<Vec<String>>().join(" "),
+            host: System::host_name().unwrap_or_default(),
+            start_time: sys
+                .process(Pid::from_u32(pid))
+                .map(|x| x.start_time())
+                .unwrap_or(0),
+        }
+    }
+
+    fn read_from_file(path: &Path) -> Result<LockHolder, Error> {
+        match toml::from_str(&fs::read_to_string(path)?) {
+            Ok(x) => Ok(x),
+            Err(e) => Err(Error::new(ErrorKind::InvalidData, e.to_string())),
+        }
+    }
+
+    ///
+    /// true if holder process is still running, or if there is no way to tell
+    ///
+    fn is_alive(&self, me: &LockHolder) -> bool {
+        if self.host != me.host {
+            return true;
+        }
+        let pid = Pid::from_u32(self.pid);
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        match sys.process(pid) {
+            Some(process) => {
+                self.start_time == 0
+                    || process.start_time() == 0
+                    || process.start_time() == self.start_time
+            }
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "process {} ({}) on host {:?}",
+            self.pid, self.command, self.host
+        )
+    }
+}
@@ -1,10 +1,15 @@
-use lifeblood_manager::installation_helpers::get_python_command;
-use lifeblood_manager::{InstallationsData, BUILD_INFO};
+use lifeblood_manager::installation_helpers::get_python_from_env;
+use lifeblood_manager::{
+    CancellationToken, InstallError, InstallEvent, InstallationsData, InstallerKind,
+    MainWidgetConfig, ProgressCallback, PythonDiscovery, SourceRef, VersionSpecifiers, BUILD_INFO,
+};
 use std::{
     env::{self, Args},
     io::Error,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+    sync::{Arc, OnceLock},
 };
 
 const MAIN_HELP_MESSAGE: &str = "\
@@ -14,6 +19,7 @@ Usage:
 
     Commangs:
         - installs
+        - python
 ";
 
 fn main() -> Result<(), Error> {
@@ -28,6 +34,13 @@ fn main() -> Result<(), Error> {
 
     match command.as_str() {
         "installs" => match process_installs(args) {
+            Err(e) => {
+                eprint!("operation failed: {}", e);
+                std::process::exit(help_exit_code(&e));
+            }
+            Ok(_) => (),
+        },
+        "python" => match process_python(args) {
             Err(e) => {
                 eprint!("operation failed: {}", e);
                 std::process::exit(1);
@@ -52,11 +65,68 @@ Usage:
     lifeblood-manager-cli installs <subcommand> <args> base_path
 
     Sub-Commangs:
-        - list
+        - list [--channel <name>]
+            --channel shows only versions of given channel
         - new
-        - set_current
+        - set_current [--this-host | --clear-this-host | --channel <name>] [<index>] <base_path>
+            --this-host gives only this host its own current version,
+            useful when base_path is shared by many hosts over network.
+            --clear-this-host makes this host use the shared current version again.
+            --channel sets current version of given channel, used by lifeblood-<name> launchers,
+            latest version of the channel if index is not given
+        - remove
+        - prune
+        - pin
+        - unpin
+        - check (exit code 0 - up to date, 3 - update available, 21 - latest commit is unknown)
+        - verify (exit code 0 - all checks passed, 20 - some failed)
+        - repair
+        - add_viewer
+        - export <index> <bundle_path> <base_path>
+            write version with wheels of all its packages into a single archive
+        - import [--ignore-system-python] <bundle_path> <base_path>
+            install version from such archive, without network access, and make it current
+
+    Exit codes of failed installation operations:
+        10 - download failed
+        11 - unpack failed
+        12 - requirements could not be read
+        13 - python is not supported
+        14 - venv creation failed
+        15 - pip failed
+        16 - links could not be made
+        17 - metadata could not be saved
+        18 - cleanup failed
+        19 - base_path is locked by another manager
+        130 - cancelled
+        1 - any other error
 ";
 
+///
+/// exit code for a failed operation, installation failures get distinct codes,
+/// so that scripts can tell, for ex, a network failure from a pip failure
+///
+fn help_exit_code(e: &Error) -> i32 {
+    let install_error = match e.get_ref().and_then(|x| x.downcast_ref::<InstallError>()) {
+        Some(x) => x,
+        None => return 1,
+    };
+    match install_error {
+        InstallError::Download(_) => 10,
+        InstallError::Unpack(_) => 11,
+        InstallError::Requirements(_) => 12,
+        InstallError::UnsupportedPython { .. } => 13,
+        InstallError::Venv(_) => 14,
+        InstallError::Pip(_) => 15,
+        InstallError::Link(_) => 16,
+        InstallError::Metadata(_) => 17,
+        InstallError::Cleanup(_) => 18,
+        InstallError::Locked(_) => 19,
+        InstallError::Cancelled => 130,
+        InstallError::Other(_) => 1,
+    }
+}
+
 fn process_installs(mut args: Args) -> Result<(), Error> {
     if args.len() < 1 {
         eprintln!("not enough arguments.");
@@ -69,6 +139,16 @@ fn process_installs(mut args: Args) -> Result<(), Error> {
         "list" => process_installs_list(args),
         "new" => process_installs_new(args),
         "set_current" => process_installs_set_current(args),
+        "remove" => process_installs_remove(args),
+        "prune" => process_installs_prune(args),
+        "pin" => process_installs_pin(args, true),
+        "unpin" => process_installs_pin(args, false),
+        "check" => process_installs_check(args),
+        "verify" => process_installs_verify(args),
+        "repair" => process_installs_repair(args),
+        "add_viewer" => process_installs_add_viewer(args),
+        "export" => process_installs_export(args),
+        "import" => process_installs_import(args),
         x => {
             eprintln!("unknown subcommand '{}'", x);
             eprintln!("{}", INSTALL_HELP_MESSAGE);
@@ -79,21 +159,95 @@ fn process_installs(mut args: Args) -> Result<(), Error> {
 
 enum InstallArgsListParsingState {
     ExpectPathOrFlag,
+    ExpectingChannel,
     NotExpectingAnything,
 }
 
 enum InstallArgsNewParsingState {
     ExpectPathOrFlag,
     ExpectingBranch,
+    ExpectingTag,
+    ExpectingCommit,
+    ExpectingZipPath,
+    ExpectingSourceUrl,
+    ExpectingSourceOwner,
+    ExpectingSourceRepo,
+    ExpectingSourceAuthHeader,
+    ExpectingCacheDir,
+    ExpectingInstaller,
+    ExpectingIndexUrl,
+    ExpectingExtraIndexUrl,
+    ExpectingTrustedHost,
+    ExpectingWheelhouse,
+    ExpectingLockFile,
     NotExpectingAnything,
 }
 
-enum InstallSetCurrentParsingState {
+enum InstallRemoveParsingState {
+    ExpectIndex,
+    ExpectPath,
+    NotExpectingAnything,
+}
+
+enum InstallPinParsingState {
+    ExpectIndex,
+    ExpectPath,
+    NotExpectingAnything,
+}
+
+enum InstallVerifyParsingState {
+    ExpectIndex,
+    ExpectPath,
+    NotExpectingAnything,
+}
+
+enum InstallRepairParsingState {
+    ExpectIndex,
+    ExpectPath,
+    NotExpectingAnything,
+}
+
+enum InstallAddViewerParsingState {
     ExpectIndex,
     ExpectPath,
     NotExpectingAnything,
 }
 
+enum InstallExportParsingState {
+    ExpectIndex,
+    ExpectBundlePath,
+    ExpectPath,
+    NotExpectingAnything,
+}
+
+enum InstallImportParsingState {
+    ExpectBundlePathOrFlag,
+    ExpectPath,
+    NotExpectingAnything,
+}
+
+enum InstallArgsPruneParsingState {
+    ExpectPathOrFlag,
+    ExpectingKeepNewest,
+    ExpectingKeepDays,
+    NotExpectingAnything,
+}
+
+enum InstallArgsCheckParsingState {
+    ExpectPathOrFlag,
+    ExpectingBranch,
+    NotExpectingAnything,
+}
+
+enum InstallSetCurrentParsingState {
+    ExpectIndexOrFlag,
+    ExpectingChannel,
+    ExpectPath,
+    // non-integer where index was expected - it's base_path if nothing follows
+    GotPathInsteadOfIndex,
+    NotExpectingAnything,
+}
+
 fn help_get_installs_from_dir(base_path: PathBuf) -> InstallationsData {
     if !base_path.exists() {
         eprintln!("given base_path {:?} does not exist", base_path);
@@ -105,7 +259,13 @@ fn help_get_installs_from_dir(base_path: PathBuf) -> InstallationsData {
     }
 
     match InstallationsData::from_dir(base_path.clone()) {
-        Ok(installs) => installs,
+        Ok(mut installs) => {
+            installs.set_progress_callback(Some(help_make_progress_printer()));
+            let manager_config = help_load_manager_config(&base_path);
+            installs.set_base_dir_lock_settings(manager_config.base_dir_lock_settings().clone());
+            installs.set_installer_settings(manager_config.installer_settings().clone());
+            installs
+        }
         Err(e) => {
             eprintln!("failed to scan given base_path {:?}: {}", base_path, e);
             std::process::exit(1);
@@ -113,12 +273,151 @@ fn help_get_installs_from_dir(base_path: PathBuf) -> InstallationsData {
     }
 }
 
+///
+/// progress callback that renders download and unpack as progress bars,
+/// and the rest as log lines
+///
+fn help_make_progress_printer() -> ProgressCallback {
+    // true if progress bar was printed last, so the line is not finished yet
+    let mid_line = AtomicBool::new(false);
+    Arc::new(move |event: &InstallEvent| {
+        let bar = match event {
+            InstallEvent::Download {
+                downloaded,
+                total: Some(total),
+            } => Some(format!(
+                "{} {:.1}/{:.1} MB",
+                help_progress_bar(*downloaded, *total),
+                *downloaded as f64 / 1048576.0,
+                *total as f64 / 1048576.0
+            )),
+            InstallEvent::Download {
+                downloaded,
+                total: None,
+            } => Some(format!(
+                "downloaded {:.1} MB",
+                *downloaded as f64 / 1048576.0
+            )),
+            InstallEvent::Unpack { done, total } => Some(format!(
+                "{} {}/{} files",
+                help_progress_bar(*done as u64, *total as u64),
+                done,
+                total
+            )),
+            _ => None,
+        };
+        if let Some(bar) = bar {
+            eprint!("\r{}", bar);
+            mid_line.store(true, Ordering::Relaxed);
+            return;
+        }
+        if mid_line.swap(false, Ordering::Relaxed) {
+            eprintln!();
+        }
+
+        match event {
+            InstallEvent::Phase(phase) => println!("==> {}", phase),
+            InstallEvent::PipOutput(line) => println!("    {}", line),
+            InstallEvent::Finished(Ok(i)) => println!("==> finished, version index: {}", i),
+            InstallEvent::Finished(Err(e)) => println!("==> failed: {}", e),
+            _ => (),
+        }
+    })
+}
+
+static CTRL_C_TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+
+///
+/// token that gets cancelled on ctrl+c, instead of ctrl+c killing us right away,
+/// so that long operations have a chance to roll back
+///
+/// second ctrl+c exits right away, in case rolling back is stuck
+///
+fn help_cancel_on_ctrl_c() -> CancellationToken {
+    CTRL_C_TOKEN
+        .get_or_init(|| {
+            let token = CancellationToken::new();
+            let handler_token = token.clone();
+            let result = ctrlc::set_handler(move || {
+                if handler_token.is_cancelled() {
+                    eprintln!("\ninterrupted");
+                    std::process::exit(130);
+                }
+                eprintln!("\ncancelling, press ctrl+c again to exit right away");
+                handler_token.cancel();
+            });
+            if let Err(e) = result {
+                eprintln!(
+                    "failed to set ctrl+c handler, ctrl+c will not be handled gracefully: {}",
+                    e
+                );
+            }
+            token
+        })
+        .clone()
+}
+
+fn help_progress_bar(done: u64, total: u64) -> String {
+    const WIDTH: u64 = 30;
+    let percent = if total == 0 {
+        100
+    } else {
+        done.min(total) * 100 / total
+    };
+    let filled = percent * WIDTH / 100;
+    format!(
+        "[{}{}] {:3}%",
+        "#".repeat(filled as usize),
+        " ".repeat((WIDTH - filled) as usize),
+        percent
+    )
+}
+
+///
+/// manager config is looked for in the base_path first,
+/// as that's where manager is supposed to live,
+/// then next to this executable
+///
+fn help_load_manager_config(base_path: &Path) -> MainWidgetConfig {
+    let mut config_dirs = vec![base_path.to_path_buf()];
+    if let Ok(exe_path) = env::current_exe() {
+        if let Some(exe_dir) = exe_path.parent() {
+            config_dirs.push(exe_dir.to_path_buf());
+        }
+    }
+
+    for config_dir in config_dirs {
+        if !config_dir.join("lifeblood-manager.config").exists() {
+            continue;
+        }
+        match MainWidgetConfig::new_from_file(&config_dir, false, false) {
+            Ok(config) => return config,
+            Err(e) => {
+                eprintln!(
+                    "failed to read manager config from {:?}, ignoring it: {}",
+                    config_dir, e
+                );
+            }
+        }
+    }
+
+    MainWidgetConfig::new_ephemeral(base_path)
+}
+
 fn process_installs_list(args: Args) -> Result<(), Error> {
     let mut state = InstallArgsListParsingState::ExpectPathOrFlag;
     let mut base_path = PathBuf::from(".");
+    let mut channel: Option<String> = None;
 
     for arg in args {
         match (state, arg) {
+            (InstallArgsListParsingState::ExpectPathOrFlag, arg) if arg == "--channel" => {
+                state = InstallArgsListParsingState::ExpectingChannel;
+            }
+            (InstallArgsListParsingState::ExpectingChannel, arg) => {
+                channel = Some(arg);
+                state = InstallArgsListParsingState::ExpectPathOrFlag;
+            }
             (InstallArgsListParsingState::ExpectPathOrFlag, arg) if arg.starts_with("--") => {
                 eprintln!("not expecting flags");
                 eprintln!("{}", INSTALL_HELP_MESSAGE);
@@ -138,23 +437,85 @@ fn process_installs_list(args: Args) -> Result<(), Error> {
 
     let installs = help_get_installs_from_dir(base_path.clone());
 
-    list_installs(&installs);
+    list_channel_installs(&installs, channel.as_deref());
 
     Ok(())
 }
 
 fn process_installs_new(args: Args) -> Result<(), Error> {
     let mut state = InstallArgsNewParsingState::ExpectPathOrFlag;
-    let mut branch = "dev".to_owned();
+    let mut source_ref = SourceRef::Branch("dev".to_owned());
+    let mut source_ref_given = false;
+    let mut from_zip: Option<PathBuf> = None;
     let mut base_path = PathBuf::from(".");
     let mut do_viewer = true;
     let mut ignore_system_python = false;
+    let mut source_url: Option<String> = None;
+    let mut source_owner: Option<String> = None;
+    let mut source_repo: Option<String> = None;
+    let mut source_auth_header: Option<String> = None;
+    let mut no_cache = false;
+    let mut cache_dir: Option<PathBuf> = None;
+    let mut installer: Option<InstallerKind> = None;
+    let mut index_url: Option<String> = None;
+    let mut extra_index_urls: Vec<String> = Vec::new();
+    let mut trusted_hosts: Vec<String> = Vec::new();
+    let mut wheelhouse: Option<PathBuf> = None;
+    let mut lock_file: Option<PathBuf> = None;
 
     for arg in args {
         match (state, arg) {
             (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--branch" => {
                 state = InstallArgsNewParsingState::ExpectingBranch
             }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--tag" => {
+                state = InstallArgsNewParsingState::ExpectingTag
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--commit" => {
+                state = InstallArgsNewParsingState::ExpectingCommit
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--from-zip" => {
+                state = InstallArgsNewParsingState::ExpectingZipPath
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--source-url" => {
+                state = InstallArgsNewParsingState::ExpectingSourceUrl
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--source-owner" => {
+                state = InstallArgsNewParsingState::ExpectingSourceOwner
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--source-repo" => {
+                state = InstallArgsNewParsingState::ExpectingSourceRepo
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg)
+                if arg == "--source-auth-header" =>
+            {
+                state = InstallArgsNewParsingState::ExpectingSourceAuthHeader
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--no-cache" => {
+                no_cache = true;
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--cache-dir" => {
+                state = InstallArgsNewParsingState::ExpectingCacheDir
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--installer" => {
+                state = InstallArgsNewParsingState::ExpectingInstaller
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--index-url" => {
+                state = InstallArgsNewParsingState::ExpectingIndexUrl
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--extra-index-url" => {
+                state = InstallArgsNewParsingState::ExpectingExtraIndexUrl
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--trusted-host" => {
+                state = InstallArgsNewParsingState::ExpectingTrustedHost
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--wheelhouse" => {
+                state = InstallArgsNewParsingState::ExpectingWheelhouse
+            }
+            (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--lock-file" => {
+                state = InstallArgsNewParsingState::ExpectingLockFile
+            }
             (InstallArgsNewParsingState::ExpectPathOrFlag, arg) if arg == "--no-viewer" => {
                 do_viewer = false;
                 state = InstallArgsNewParsingState::ExpectPathOrFlag;
@@ -170,7 +531,81 @@ fn process_installs_new(args: Args) -> Result<(), Error> {
                 state = InstallArgsNewParsingState::NotExpectingAnything;
             }
             (InstallArgsNewParsingState::ExpectingBranch, arg) => {
-                branch = arg;
+                source_ref = SourceRef::Branch(arg);
+                source_ref_given = true;
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingTag, arg) => {
+                source_ref = SourceRef::Tag(arg);
+                source_ref_given = true;
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingCommit, arg) => {
+                source_ref = match format!("commit:{}", arg).parse() {
+                    Ok(x) => x,
+                    Err(e) => {
+                        eprintln!("bad commit: {}", e);
+                        eprintln!("{}", INSTALL_HELP_MESSAGE);
+                        std::process::exit(2);
+                    }
+                };
+                source_ref_given = true;
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingZipPath, arg) => {
+                from_zip = Some(PathBuf::from(arg));
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingSourceUrl, arg) => {
+                source_url = Some(arg);
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingSourceOwner, arg) => {
+                source_owner = Some(arg);
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingSourceRepo, arg) => {
+                source_repo = Some(arg);
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingSourceAuthHeader, arg) => {
+                source_auth_header = Some(arg);
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingCacheDir, arg) => {
+                cache_dir = Some(PathBuf::from(arg));
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingInstaller, arg) => {
+                installer = match arg.parse::<InstallerKind>() {
+                    Ok(x) => Some(x),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        eprintln!("{}", INSTALL_HELP_MESSAGE);
+                        std::process::exit(2);
+                    }
+                };
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingIndexUrl, arg) => {
+                index_url = Some(arg);
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingExtraIndexUrl, arg) => {
+                extra_index_urls.push(arg);
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingTrustedHost, arg) => {
+                trusted_hosts.push(arg);
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingWheelhouse, arg) => {
+                // relative to where we are, not to base_path as in config
+                wheelhouse = Some(std::path::absolute(arg)?);
+                state = InstallArgsNewParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsNewParsingState::ExpectingLockFile, arg) => {
+                lock_file = Some(PathBuf::from(arg));
                 state = InstallArgsNewParsingState::ExpectPathOrFlag;
             }
             (InstallArgsNewParsingState::NotExpectingAnything, _) => {
@@ -181,25 +616,79 @@ fn process_installs_new(args: Args) -> Result<(), Error> {
         }
     }
 
+    // archive already is some commit, there's nothing to choose
+    if from_zip.is_some() && source_ref_given {
+        eprintln!("--from-zip cannot be used with --branch, --tag or --commit");
+        eprintln!("{}", INSTALL_HELP_MESSAGE);
+        std::process::exit(2);
+    }
+
+    // unless python is given explicitly - the newest suitable one is found during install
     let path_to_python = if ignore_system_python {
         None
     } else {
-        get_python_command()
+        get_python_from_env()
     };
 
-    let mut installs = help_get_installs_from_dir(base_path);
+    let mut installs = help_get_installs_from_dir(base_path.clone());
+    installs.set_auto_select_python(!ignore_system_python);
 
-    let new_ver_index =
-        match installs.download_new_version(&branch, do_viewer, path_to_python.as_deref()) {
-            Ok(i) => {
-                println!("New version downloaded");
-                i
-            }
-            Err(e) => {
-                eprintln!("Failed to get latest version: {}", e);
-                return Err(e);
-            }
-        };
+    // download source and cache from manager config, overridden by flags
+    let manager_config = help_load_manager_config(&base_path);
+    let mut download_source = manager_config.download_source().clone();
+    if let Some(x) = source_url {
+        download_source.base_url_template = x;
+    }
+    if let Some(x) = source_owner {
+        download_source.owner = x;
+    }
+    if let Some(x) = source_repo {
+        download_source.repo = x;
+    }
+    if let Some(x) = source_auth_header {
+        download_source.auth_header = Some(x);
+    }
+    installs.set_download_source(download_source);
+    let mut cache_settings = manager_config.download_cache_settings().clone();
+    if no_cache {
+        cache_settings.enabled = false;
+    }
+    if let Some(x) = cache_dir {
+        cache_settings.location = Some(x);
+    }
+    installs.set_download_cache_settings(cache_settings);
+    installs.set_retention_policy(manager_config.retention_policy().clone());
+    let mut installer_settings = installs.installer_settings().clone();
+    if let Some(x) = installer {
+        installer_settings.backend = x;
+    }
+    if let Some(x) = index_url {
+        installer_settings.index_url = Some(x);
+    }
+    installer_settings.extra_index_urls.extend(extra_index_urls);
+    installer_settings.trusted_hosts.extend(trusted_hosts);
+    if let Some(x) = wheelhouse {
+        installer_settings.wheelhouse = Some(x);
+    }
+    installs.set_installer_settings(installer_settings);
+    installs.set_requirements_lock(lock_file);
+    installs.set_cancellation_token(Some(help_cancel_on_ctrl_c()));
+
+    let result = if let Some(zip_path) = from_zip {
+        installs.install_from_zip(&zip_path, do_viewer, path_to_python.as_deref())
+    } else {
+        installs.download_new_version(&source_ref, do_viewer, path_to_python.as_deref())
+    };
+    let new_ver_index = match result {
+        Ok(i) => {
+            println!("New version downloaded");
+            i
+        }
+        Err(e) => {
+            eprintln!("Failed to get latest version: {}", e);
+            return Err(e.into());
+        }
+    };
     match installs.make_version_current(new_ver_index) {
         Ok(_) => {
             println!("New version is set as current");
@@ -207,7 +696,7 @@ fn process_installs_new(args: Args) -> Result<(), Error> {
         Err(e) => {
             eprintln!("Failed to set new version as current: {}", e);
             list_installs(&installs);
-            return Err(e);
+            return Err(e.into());
         }
     }
 
@@ -217,25 +706,53 @@ fn process_installs_new(args: Args) -> Result<(), Error> {
 }
 
 fn process_installs_set_current(args: Args) -> Result<(), Error> {
-    let mut state = InstallSetCurrentParsingState::ExpectIndex;
+    let mut state = InstallSetCurrentParsingState::ExpectIndexOrFlag;
     let mut base_path = PathBuf::from(".");
     let mut index: usize = 0;
     let mut index_provided: bool = false;
+    let mut this_host = false;
+    let mut clear_this_host = false;
+    let mut channel: Option<String> = None;
 
     for arg in args {
         match (state, arg) {
-            (InstallSetCurrentParsingState::ExpectIndex, arg) => {
-                index = match usize::from_str(&arg) {
-                    Ok(i) => i,
+            (InstallSetCurrentParsingState::ExpectIndexOrFlag, arg) if arg == "--this-host" => {
+                this_host = true;
+                state = InstallSetCurrentParsingState::ExpectIndexOrFlag;
+            }
+            (InstallSetCurrentParsingState::ExpectIndexOrFlag, arg)
+                if arg == "--clear-this-host" =>
+            {
+                // no index needed to go back to the shared current
+                clear_this_host = true;
+                state = InstallSetCurrentParsingState::ExpectPath;
+            }
+            (InstallSetCurrentParsingState::ExpectIndexOrFlag, arg) if arg == "--channel" => {
+                state = InstallSetCurrentParsingState::ExpectingChannel;
+            }
+            (InstallSetCurrentParsingState::ExpectingChannel, arg) => {
+                channel = Some(arg);
+                state = InstallSetCurrentParsingState::ExpectIndexOrFlag;
+            }
+            (InstallSetCurrentParsingState::ExpectIndexOrFlag, arg) => {
+                match usize::from_str(&arg) {
+                    Ok(i) => {
+                        index = i;
+                        index_provided = true;
+                        state = InstallSetCurrentParsingState::ExpectPath;
+                    }
+                    // index is optional
                     Err(_) => {
-                        return Err(Error::new(
-                            std::io::ErrorKind::InvalidData,
-                            "given index is not an integer",
-                        ));
+                        base_path = PathBuf::from(arg);
+                        state = InstallSetCurrentParsingState::GotPathInsteadOfIndex;
                     }
-                };
-                index_provided = true;
-                state = InstallSetCurrentParsingState::ExpectPath;
+                }
+            }
+            (InstallSetCurrentParsingState::GotPathInsteadOfIndex, _) => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "given index is not an integer",
+                ));
             }
             (InstallSetCurrentParsingState::ExpectPath, arg) => {
                 base_path = PathBuf::from(arg);
@@ -249,8 +766,61 @@ fn process_installs_set_current(args: Args) -> Result<(), Error> {
         }
     }
 
+    if [this_host, clear_this_host, channel.is_some()]
+        .iter()
+        .filter(|x| **x)
+        .count()
+        > 1
+    {
+        eprintln!("only one of --this-host, --clear-this-host and --channel can be used");
+        eprintln!("{}", INSTALL_HELP_MESSAGE);
+        std::process::exit(2);
+    }
+
     let mut installs = help_get_installs_from_dir(base_path.clone());
 
+    if clear_this_host {
+        installs.set_host_current(None)?;
+        list_installs(&installs);
+        return Ok(());
+    }
+
+    if let Some(channel) = channel {
+        if !index_provided {
+            index = match installs.channel_latest_version_index(&channel) {
+                Some(i) => i,
+                None => {
+                    return Err(Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("channel {} has no versions", channel),
+                    ));
+                }
+            };
+        }
+        match installs.version(index) {
+            Some(ver) if ver.channel() == Some(channel.as_str()) => (),
+            Some(ver) => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "version {} does not belong to channel {}",
+                        ver.nice_name(),
+                        channel
+                    ),
+                ));
+            }
+            None => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "version index out of range",
+                ));
+            }
+        }
+        installs.make_version_channel_current(index)?;
+        list_channel_installs(&installs, Some(&channel));
+        return Ok(());
+    }
+
     if !index_provided {
         index = installs.version_count();
         if index > 0 {
@@ -265,35 +835,728 @@ fn process_installs_set_current(args: Args) -> Result<(), Error> {
         ));
     }
 
-    installs.make_version_current(index)?;
+    if this_host {
+        installs.set_host_current(Some(index))?;
+    } else {
+        installs.make_version_current(index)?;
+    }
 
     list_installs(&installs);
 
     Ok(())
 }
 
-fn list_installs(installs: &InstallationsData) {
-    println!("valid base path: {:?}", installs.base_path());
-    if installs.is_base_path_tainted() {
-        println!("    Warning: given path contains elements unrelated to lifeblood.");
-        println!("    It's recommended to choose an empty directory for lifeblood installations");
+fn process_installs_remove(args: Args) -> Result<(), Error> {
+    let mut state = InstallRemoveParsingState::ExpectIndex;
+    let mut base_path = PathBuf::from(".");
+    let mut index: Option<usize> = None;
+
+    for arg in args {
+        match (state, arg) {
+            (InstallRemoveParsingState::ExpectIndex, arg) => {
+                index = match usize::from_str(&arg) {
+                    Ok(i) => Some(i),
+                    Err(_) => {
+                        return Err(Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "given index is not an integer",
+                        ));
+                    }
+                };
+                state = InstallRemoveParsingState::ExpectPath;
+            }
+            (InstallRemoveParsingState::ExpectPath, arg) => {
+                base_path = PathBuf::from(arg);
+                state = InstallRemoveParsingState::NotExpectingAnything;
+            }
+            (InstallRemoveParsingState::NotExpectingAnything, _) => {
+                eprintln!("not expecting any more arguments after base_path");
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+        }
     }
-    println!("");
-    if installs.version_count() == 0 {
-        println!("No installations found")
+
+    let index = match index {
+        Some(i) => i,
+        None => {
+            eprintln!("index of version to remove must be provided");
+            eprintln!("{}", INSTALL_HELP_MESSAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut installs = help_get_installs_from_dir(base_path.clone());
+
+    installs.remove_version(index)?;
+
+    list_installs(&installs);
+
+    Ok(())
+}
+
+fn process_installs_pin(args: Args, pin: bool) -> Result<(), Error> {
+    let mut state = InstallPinParsingState::ExpectIndex;
+    let mut base_path = PathBuf::from(".");
+    let mut index: Option<usize> = None;
+
+    for arg in args {
+        match (state, arg) {
+            (InstallPinParsingState::ExpectIndex, arg) => {
+                index = match usize::from_str(&arg) {
+                    Ok(i) => Some(i),
+                    Err(_) => {
+                        return Err(Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "given index is not an integer",
+                        ));
+                    }
+                };
+                state = InstallPinParsingState::ExpectPath;
+            }
+            (InstallPinParsingState::ExpectPath, arg) => {
+                base_path = PathBuf::from(arg);
+                state = InstallPinParsingState::NotExpectingAnything;
+            }
+            (InstallPinParsingState::NotExpectingAnything, _) => {
+                eprintln!("not expecting any more arguments after base_path");
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+        }
     }
-    for (i, ver) in installs.iter_versions().enumerate().rev() {
-        println!(
-            "{:3} | {} | {} | {} | {}",
-            i,
+
+    let index = match index {
+        Some(i) => i,
+        None => {
+            eprintln!("index of version to (un)pin must be provided");
+            eprintln!("{}", INSTALL_HELP_MESSAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut installs = help_get_installs_from_dir(base_path.clone());
+
+    if pin {
+        installs.pin_version(index)?;
+    } else {
+        installs.unpin_version(index)?;
+    }
+
+    list_installs(&installs);
+
+    Ok(())
+}
+
+///
+/// exit code for when version is there, but it's broken,
+/// so that it can be told apart from failing to verify at all
+///
+const EXIT_CODE_VERIFY_FAILED: i32 = 20;
+
+fn process_installs_verify(args: Args) -> Result<(), Error> {
+    let mut state = InstallVerifyParsingState::ExpectIndex;
+    let mut base_path = PathBuf::from(".");
+    let mut index: Option<usize> = None;
+
+    for arg in args {
+        match (state, arg) {
+            (InstallVerifyParsingState::ExpectIndex, arg) => {
+                index = match usize::from_str(&arg) {
+                    Ok(i) => Some(i),
+                    Err(_) => {
+                        return Err(Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "given index is not an integer",
+                        ));
+                    }
+                };
+                state = InstallVerifyParsingState::ExpectPath;
+            }
+            (InstallVerifyParsingState::ExpectPath, arg) => {
+                base_path = PathBuf::from(arg);
+                state = InstallVerifyParsingState::NotExpectingAnything;
+            }
+            (InstallVerifyParsingState::NotExpectingAnything, _) => {
+                eprintln!("not expecting any more arguments after base_path");
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let index = match index {
+        Some(i) => i,
+        None => {
+            eprintln!("index of version to verify must be provided");
+            eprintln!("{}", INSTALL_HELP_MESSAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let installs = help_get_installs_from_dir(base_path.clone());
+
+    let report = installs.verify_version(index)?;
+    for check in report.checks() {
+        println!("{}", check);
+    }
+
+    if !report.is_ok() {
+        eprintln!("some verification checks failed");
+        std::process::exit(EXIT_CODE_VERIFY_FAILED);
+    }
+
+    Ok(())
+}
+
+fn process_installs_repair(args: Args) -> Result<(), Error> {
+    let mut state = InstallRepairParsingState::ExpectIndex;
+    let mut base_path = PathBuf::from(".");
+    let mut index: Option<usize> = None;
+
+    for arg in args {
+        match (state, arg) {
+            (InstallRepairParsingState::ExpectIndex, arg) => {
+                index = match usize::from_str(&arg) {
+                    Ok(i) => Some(i),
+                    Err(_) => {
+                        return Err(Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "given index is not an integer",
+                        ));
+                    }
+                };
+                state = InstallRepairParsingState::ExpectPath;
+            }
+            (InstallRepairParsingState::ExpectPath, arg) => {
+                base_path = PathBuf::from(arg);
+                state = InstallRepairParsingState::NotExpectingAnything;
+            }
+            (InstallRepairParsingState::NotExpectingAnything, _) => {
+                eprintln!("not expecting any more arguments after base_path");
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let index = match index {
+        Some(i) => i,
+        None => {
+            eprintln!("index of version to repair must be provided");
+            eprintln!("{}", INSTALL_HELP_MESSAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut installs = help_get_installs_from_dir(base_path.clone());
+    installs.set_cancellation_token(Some(help_cancel_on_ctrl_c()));
+
+    installs.repair_version(index)?;
+
+    let report = installs.verify_version(index)?;
+    for check in report.checks() {
+        println!("{}", check);
+    }
+
+    Ok(())
+}
+
+fn process_installs_add_viewer(args: Args) -> Result<(), Error> {
+    let mut state = InstallAddViewerParsingState::ExpectIndex;
+    let mut base_path = PathBuf::from(".");
+    let mut index: Option<usize> = None;
+
+    for arg in args {
+        match (state, arg) {
+            (InstallAddViewerParsingState::ExpectIndex, arg) => {
+                index = match usize::from_str(&arg) {
+                    Ok(i) => Some(i),
+                    Err(_) => {
+                        return Err(Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "given index is not an integer",
+                        ));
+                    }
+                };
+                state = InstallAddViewerParsingState::ExpectPath;
+            }
+            (InstallAddViewerParsingState::ExpectPath, arg) => {
+                base_path = PathBuf::from(arg);
+                state = InstallAddViewerParsingState::NotExpectingAnything;
+            }
+            (InstallAddViewerParsingState::NotExpectingAnything, _) => {
+                eprintln!("not expecting any more arguments after base_path");
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let index = match index {
+        Some(i) => i,
+        None => {
+            eprintln!("index of version to add viewer to must be provided");
+            eprintln!("{}", INSTALL_HELP_MESSAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut installs = help_get_installs_from_dir(base_path.clone());
+    let config = help_load_manager_config(&base_path);
+    installs.set_download_source(config.download_source().clone());
+    installs.set_download_cache_settings(config.download_cache_settings().clone());
+    installs.set_cancellation_token(Some(help_cancel_on_ctrl_c()));
+
+    installs.add_viewer(index)?;
+
+    list_installs(&installs);
+
+    Ok(())
+}
+
+fn process_installs_export(args: Args) -> Result<(), Error> {
+    let mut state = InstallExportParsingState::ExpectIndex;
+    let mut base_path = PathBuf::from(".");
+    let mut index: Option<usize> = None;
+    let mut bundle_path: Option<PathBuf> = None;
+
+    for arg in args {
+        match (state, arg) {
+            (InstallExportParsingState::ExpectIndex, arg) => {
+                index = match usize::from_str(&arg) {
+                    Ok(i) => Some(i),
+                    Err(_) => {
+                        return Err(Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "given index is not an integer",
+                        ));
+                    }
+                };
+                state = InstallExportParsingState::ExpectBundlePath;
+            }
+            (InstallExportParsingState::ExpectBundlePath, arg) => {
+                bundle_path = Some(PathBuf::from(arg));
+                state = InstallExportParsingState::ExpectPath;
+            }
+            (InstallExportParsingState::ExpectPath, arg) => {
+                base_path = PathBuf::from(arg);
+                state = InstallExportParsingState::NotExpectingAnything;
+            }
+            (InstallExportParsingState::NotExpectingAnything, _) => {
+                eprintln!("not expecting any more arguments after base_path");
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let (index, bundle_path) = match (index, bundle_path) {
+        (Some(i), Some(x)) => (i, x),
+        _ => {
+            eprintln!("index of version to export and bundle path must be provided");
+            eprintln!("{}", INSTALL_HELP_MESSAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut installs = help_get_installs_from_dir(base_path.clone());
+    installs.set_cancellation_token(Some(help_cancel_on_ctrl_c()));
+
+    installs.export_version(index, &bundle_path)?;
+    println!("version {} exported to {:?}", index, bundle_path);
+
+    Ok(())
+}
+
+fn process_installs_import(args: Args) -> Result<(), Error> {
+    let mut state = InstallImportParsingState::ExpectBundlePathOrFlag;
+    let mut base_path = PathBuf::from(".");
+    let mut bundle_path: Option<PathBuf> = None;
+    let mut ignore_system_python = false;
+
+    for arg in args {
+        match (state, arg) {
+            (InstallImportParsingState::ExpectBundlePathOrFlag, arg)
+                if arg == "--ignore-system-python" =>
+            {
+                ignore_system_python = true;
+                state = InstallImportParsingState::ExpectBundlePathOrFlag;
+            }
+            (InstallImportParsingState::ExpectBundlePathOrFlag, arg) => {
+                bundle_path = Some(PathBuf::from(arg));
+                state = InstallImportParsingState::ExpectPath;
+            }
+            (InstallImportParsingState::ExpectPath, arg) => {
+                base_path = PathBuf::from(arg);
+                state = InstallImportParsingState::NotExpectingAnything;
+            }
+            (InstallImportParsingState::NotExpectingAnything, _) => {
+                eprintln!("not expecting any more arguments after base_path");
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let bundle_path = match bundle_path {
+        Some(x) => x,
+        None => {
+            eprintln!("bundle path must be provided");
+            eprintln!("{}", INSTALL_HELP_MESSAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // unless python is given explicitly - one matching the bundle is found during import
+    let path_to_python = if ignore_system_python {
+        None
+    } else {
+        get_python_from_env()
+    };
+
+    let mut installs = help_get_installs_from_dir(base_path.clone());
+    installs.set_auto_select_python(!ignore_system_python);
+    installs.set_cancellation_token(Some(help_cancel_on_ctrl_c()));
+
+    let new_ver_index = installs.import_bundle(&bundle_path, path_to_python.as_deref())?;
+    println!("Bundle imported");
+    match installs.make_version_current(new_ver_index) {
+        Ok(_) => {
+            println!("Imported version is set as current");
+        }
+        Err(e) => {
+            eprintln!("Failed to set imported version as current: {}", e);
+            list_installs(&installs);
+            return Err(e.into());
+        }
+    }
+
+    list_installs(&installs);
+
+    Ok(())
+}
+
+fn process_installs_prune(args: Args) -> Result<(), Error> {
+    let mut state = InstallArgsPruneParsingState::ExpectPathOrFlag;
+    let mut base_path = PathBuf::from(".");
+    let mut dry_run = false;
+    let mut keep_newest: Option<usize> = None;
+    let mut keep_days: Option<u32> = None;
+
+    for arg in args {
+        match (state, arg) {
+            (InstallArgsPruneParsingState::ExpectPathOrFlag, arg) if arg == "--dry-run" => {
+                dry_run = true;
+                state = InstallArgsPruneParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsPruneParsingState::ExpectPathOrFlag, arg) if arg == "--keep-newest" => {
+                state = InstallArgsPruneParsingState::ExpectingKeepNewest
+            }
+            (InstallArgsPruneParsingState::ExpectPathOrFlag, arg) if arg == "--keep-days" => {
+                state = InstallArgsPruneParsingState::ExpectingKeepDays
+            }
+            (InstallArgsPruneParsingState::ExpectPathOrFlag, arg) if arg.starts_with("--") => {
+                eprintln!("unknown flag {}", arg);
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+            (InstallArgsPruneParsingState::ExpectPathOrFlag, arg) => {
+                base_path = PathBuf::from(arg);
+                state = InstallArgsPruneParsingState::NotExpectingAnything;
+            }
+            (InstallArgsPruneParsingState::ExpectingKeepNewest, arg) => {
+                keep_newest = match usize::from_str(&arg) {
+                    Ok(x) => Some(x),
+                    Err(_) => {
+                        eprintln!("--keep-newest expects an integer");
+                        std::process::exit(2);
+                    }
+                };
+                state = InstallArgsPruneParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsPruneParsingState::ExpectingKeepDays, arg) => {
+                keep_days = match u32::from_str(&arg) {
+                    Ok(x) => Some(x),
+                    Err(_) => {
+                        eprintln!("--keep-days expects an integer");
+                        std::process::exit(2);
+                    }
+                };
+                state = InstallArgsPruneParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsPruneParsingState::NotExpectingAnything, _) => {
+                eprintln!("not expecting any more arguments after base_path");
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let mut installs = help_get_installs_from_dir(base_path.clone());
+
+    // retention policy from manager config, overridden by flags
+    let mut policy = help_load_manager_config(&base_path)
+        .retention_policy()
+        .clone();
+    if keep_newest.is_some() {
+        policy.keep_newest = keep_newest;
+    }
+    if keep_days.is_some() {
+        policy.keep_days = keep_days;
+    }
+    if !policy.is_enabled() {
+        println!("no retention policy set, nothing to prune");
+        return Ok(());
+    }
+    installs.set_retention_policy(policy);
+
+    if dry_run {
+        let to_prune = installs.versions_to_prune();
+        if to_prune.is_empty() {
+            println!("nothing to prune");
+        }
+        for i in to_prune {
+            let ver = installs.version(i).unwrap();
+            println!("would remove {:3} | {} | {}", i, ver.nice_name(), ver.source_commit());
+        }
+        return Ok(());
+    }
+
+    for name in installs.prune_versions()? {
+        println!("removed {}", name);
+    }
+
+    list_installs(&installs);
+
+    Ok(())
+}
+
+///
+/// exit code for when an update is available,
+/// so that it can be told apart from errors
+///
+const EXIT_CODE_UPDATE_AVAILABLE: i32 = 3;
+
+///
+/// exit code for when latest commit could not be found out, usually a network problem
+///
+const EXIT_CODE_CHECK_FAILED: i32 = 21;
+
+fn process_installs_check(args: Args) -> Result<(), Error> {
+    let mut state = InstallArgsCheckParsingState::ExpectPathOrFlag;
+    let mut branch = "dev".to_owned();
+    let mut base_path = PathBuf::from(".");
+
+    for arg in args {
+        match (state, arg) {
+            (InstallArgsCheckParsingState::ExpectPathOrFlag, arg) if arg == "--branch" => {
+                state = InstallArgsCheckParsingState::ExpectingBranch
+            }
+            (InstallArgsCheckParsingState::ExpectPathOrFlag, arg) if arg.starts_with("--") => {
+                eprintln!("unknown flag {}", arg);
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+            (InstallArgsCheckParsingState::ExpectPathOrFlag, arg) => {
+                base_path = PathBuf::from(arg);
+                state = InstallArgsCheckParsingState::NotExpectingAnything;
+            }
+            (InstallArgsCheckParsingState::ExpectingBranch, arg) => {
+                branch = arg;
+                state = InstallArgsCheckParsingState::ExpectPathOrFlag;
+            }
+            (InstallArgsCheckParsingState::NotExpectingAnything, _) => {
+                eprintln!("not expecting any more arguments after base_path");
+                eprintln!("{}", INSTALL_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let mut installs = help_get_installs_from_dir(base_path.clone());
+    installs.set_download_source(
+        help_load_manager_config(&base_path)
+            .download_source()
+            .clone(),
+    );
+
+    let update_info = match installs.check_for_update(&branch) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("failed to get latest commit of {}: {}", branch, e);
+            std::process::exit(EXIT_CODE_CHECK_FAILED);
+        }
+    };
+    println!(
+        "latest commit of {}: {} | {}",
+        branch,
+        update_info.latest_commit(),
+        update_info
+            .latest_date()
+            .format("%d-%m-%Y %H:%M:%S")
+            .to_string(),
+    );
+    match update_info.installed_version_index() {
+        Some(i) => {
+            println!(
+                "up to date, installed as {:3} | {}",
+                i,
+                installs.version(i).unwrap().nice_name()
+            );
+        }
+        None => {
+            println!("update available");
+            std::process::exit(EXIT_CODE_UPDATE_AVAILABLE);
+        }
+    }
+
+    Ok(())
+}
+
+fn list_installs(installs: &InstallationsData) {
+    list_channel_installs(installs, None);
+}
+
+///
+/// list versions of given channel only, or all of them
+///
+fn list_channel_installs(installs: &InstallationsData, channel: Option<&str>) {
+    println!("valid base path: {:?}", installs.base_path());
+    if installs.is_base_path_tainted() {
+        println!("    Warning: given path contains elements unrelated to lifeblood.");
+        println!("    It's recommended to choose an empty directory for lifeblood installations");
+    }
+    if installs.has_host_current() {
+        println!("This host has its own current version, other hosts may have another one");
+    }
+    let help_index_str = |x: Option<usize>| match x {
+        Some(i) => i.to_string(),
+        None => "none".to_owned(),
+    };
+    for name in installs.channels() {
+        if channel.is_some_and(|x| x != name) {
+            continue;
+        }
+        println!(
+            "channel {}: current {}, latest {}",
+            name,
+            help_index_str(installs.channel_current_version_index(&name)),
+            help_index_str(installs.channel_latest_version_index(&name)),
+        );
+    }
+    println!("");
+    if installs.version_count() == 0 {
+        println!("No installations found")
+    }
+    for (i, ver) in installs.iter_versions().enumerate().rev() {
+        if channel.is_some_and(|x| ver.channel() != Some(x)) {
+            continue;
+        }
+        println!(
+            "{:3} | {} | {} | {} | {} | {} | {}",
+            i,
             if installs.current_version_index() == i {
                 "current"
             } else {
                 "       "
             },
+            if ver.is_pinned() { "pinned" } else { "      " },
             ver.nice_name(),
             ver.date().format("%d-%m-%Y %H:%M:%S").to_string(),
             ver.source_commit(),
+            if let Some(source_ref) = ver.source_ref() {
+                source_ref.to_string()
+            } else {
+                "unknown ref".to_owned()
+            },
         );
     }
 }
+
+const PYTHON_HELP_MESSAGE: &str = "\
+Usage:
+    lifeblood-manager-cli python <subcommand> <args>
+
+    Sub-Commangs:
+        - list [--requires <specifiers>]
+            show pythons found on this machine, the one that would be used is marked with *
+            and why others would not be used.
+            --requires checks them against given python versions, like \">=3.8,<3.13\"
+";
+
+fn process_python(mut args: Args) -> Result<(), Error> {
+    if args.len() < 1 {
+        eprintln!("not enough arguments.");
+        eprintln!("{}", PYTHON_HELP_MESSAGE);
+        std::process::exit(2);
+    }
+
+    let subcommand = args.next().unwrap(); // should not error, as we checked len
+    match subcommand.as_str() {
+        "list" => process_python_list(args),
+        x => {
+            eprintln!("unknown subcommand '{}'", x);
+            eprintln!("{}", PYTHON_HELP_MESSAGE);
+            std::process::exit(2);
+        }
+    }
+}
+
+enum PythonArgsListParsingState {
+    ExpectFlag,
+    ExpectingRequires,
+}
+
+fn process_python_list(args: Args) -> Result<(), Error> {
+    let mut state = PythonArgsListParsingState::ExpectFlag;
+    let mut requires = VersionSpecifiers::default();
+
+    for arg in args {
+        match (state, arg) {
+            (PythonArgsListParsingState::ExpectFlag, arg) if arg == "--requires" => {
+                state = PythonArgsListParsingState::ExpectingRequires
+            }
+            (PythonArgsListParsingState::ExpectFlag, arg) => {
+                eprintln!("unexpected argument {}", arg);
+                eprintln!("{}", PYTHON_HELP_MESSAGE);
+                std::process::exit(2);
+            }
+            (PythonArgsListParsingState::ExpectingRequires, arg) => {
+                requires = match arg.parse() {
+                    Ok(x) => x,
+                    Err(e) => {
+                        eprintln!("bad --requires: {}", e);
+                        std::process::exit(2);
+                    }
+                };
+                state = PythonArgsListParsingState::ExpectFlag;
+            }
+        }
+    }
+
+    let discovery = PythonDiscovery::run();
+    let chosen = discovery.choose(&requires).map(|x| x.path().to_path_buf());
+    println!("pythons found, checked against: {}", requires);
+    for candidate in discovery.candidates() {
+        println!(
+            "{} {:12} | {:15} | {:?}",
+            if Some(candidate.path()) == chosen.as_deref() {
+                "*"
+            } else {
+                " "
+            },
+            candidate.version().unwrap_or("-"),
+            candidate.source().to_string(),
+            candidate.path(),
+        );
+        if let Some(reason) = candidate.rejection_reason(&requires) {
+            println!("      rejected: {}", reason);
+        }
+    }
+    if chosen.is_none() {
+        println!("no suitable python found");
+    }
+
+    Ok(())
+}
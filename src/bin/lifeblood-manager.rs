@@ -293,13 +293,34 @@ impl MainWidget {
 
     pub fn change_install_dir(&mut self, new_path: &Path) {
         // update input
+        // same as the cli - config in the installation dir comes first, then our own
+        let install_dir_config = match MainWidgetConfig::new_from_install_dir(new_path) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!(
+                    "failed to read manager config from {:?}, ignoring it: {}",
+                    new_path, e
+                );
+                None
+            }
+        };
+        let own_config = self.config.borrow();
+        let install_config = install_dir_config.as_ref().unwrap_or(&own_config);
         self.install_data = match InstallationsData::from_dir(new_path.to_path_buf()) {
-            Ok(x) => Some(Arc::new(Mutex::new(x))),
+            Ok(mut x) => {
+                x.set_download_source(install_config.download_source().clone());
+                x.set_download_cache_settings(install_config.download_cache_settings().clone());
+                x.set_retention_policy(install_config.retention_policy().clone());
+                x.set_base_dir_lock_settings(install_config.base_dir_lock_settings().clone());
+                x.set_installer_settings(install_config.installer_settings().clone());
+                Some(Arc::new(Mutex::new(x)))
+            }
             _ => {
                 println!("no versions found");
                 None
             }
         };
+        drop(own_config);
         self.base_path_input.set_value(&new_path.to_string_lossy());
         for widget_to_cb in self.sub_widgets.iter_mut() {
             widget_to_cb
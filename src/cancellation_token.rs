@@ -0,0 +1,40 @@
+use std::io::{Error, ErrorKind};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+///
+/// a flag to ask a long operation to stop
+///
+/// clones share the same flag, so one clone can be given to the operation,
+/// and the other one kept to cancel it from another thread
+///
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    ///
+    /// error if cancelled, to be checked between phases of an operation
+    ///
+    /// error kind is Interrupted, but only is_cancelled tells that it was cancellation
+    ///
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        if self.is_cancelled() {
+            return Err(Error::new(ErrorKind::Interrupted, "operation was cancelled"));
+        }
+        Ok(())
+    }
+}
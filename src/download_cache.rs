@@ -0,0 +1,310 @@
+use crate::base_dir_lock::BaseDirLock;
+use crate::{BaseDirLockSettings, CancellationToken, SourceRef};
+use rand::{thread_rng, Rng};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Error};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+///
+/// name of the cache dir inside base install path, used when no other location is configured
+///
+pub const DOWNLOAD_CACHE_DIR_NAME: &str = ".download_cache";
+const INDEX_FILE_NAME: &str = "index.toml";
+/// index updates are quick, so if cache stays locked for longer - something is wrong
+const LOCK_TIMEOUT_SECS: u64 = 60;
+
+///
+/// settings of the cache of downloaded source archives
+///
+/// archives are kept by commit, so they can be reused by repeated installs
+/// and by other machines sharing the same base install path
+///
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct DownloadCacheSettings {
+    pub enabled: bool,
+    /// cache location, if None - DOWNLOAD_CACHE_DIR_NAME inside base install path is used
+    pub location: Option<PathBuf>,
+    /// when cache grows bigger than this - least recently used archives are removed
+    pub size_limit_mb: u64,
+}
+
+impl Default for DownloadCacheSettings {
+    fn default() -> Self {
+        DownloadCacheSettings {
+            enabled: true,
+            location: None,
+            size_limit_mb: 1024,
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Default)]
+struct CacheIndex {
+    #[serde(default)]
+    archives: Vec<CachedArchive>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+struct CachedArchive {
+    commit: String,
+    file_name: String,
+    sha256: String,
+    size: u64,
+    last_used: i64,
+    /// tags known to point to this commit
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+pub(crate) struct DownloadCache {
+    location: PathBuf,
+    size_limit: u64,
+    cancel: CancellationToken,
+    lock_held: Arc<AtomicBool>,
+}
+
+impl DownloadCache {
+    ///
+    /// lock_held is the held flag of the lock of cache location,
+    /// if the caller may already hold it - as when cache is right in the base install path
+    ///
+    pub(crate) fn new(
+        location: PathBuf,
+        size_limit_mb: u64,
+        cancel: CancellationToken,
+        lock_held: Option<Arc<AtomicBool>>,
+    ) -> DownloadCache {
+        DownloadCache {
+            location,
+            size_limit: size_limit_mb * 1024 * 1024,
+            cancel,
+            lock_held: lock_held.unwrap_or_default(),
+        }
+    }
+
+    ///
+    /// lock the cache dir for reading and updating the index
+    ///
+    /// cache may be shared by several managers, even on different machines,
+    /// so index is never changed without holding this lock
+    ///
+    fn lock(&self) -> Result<Option<BaseDirLock>, Error> {
+        BaseDirLock::acquire(
+            &self.location,
+            &BaseDirLockSettings {
+                wait: true,
+                wait_timeout_secs: Some(LOCK_TIMEOUT_SECS),
+            },
+            &self.lock_held,
+            &self.cancel,
+        )
+    }
+
+    ///
+    /// true if nothing was ever cached here
+    ///
+    pub(crate) fn is_empty(&self) -> bool {
+        self.read_index().archives.is_empty()
+    }
+
+    ///
+    /// find cached archive for given ref
+    ///
+    /// only commits and tags can be found, as branch head may move at any moment,
+    /// so branch has to be resolved to its head commit first
+    /// archive with mismatching checksum is considered corrupted and is removed from cache
+    ///
+    pub(crate) fn find(&self, source_ref: &SourceRef) -> Option<PathBuf> {
+        if !self.location.join(INDEX_FILE_NAME).exists() {
+            return None;
+        }
+        let _lock = match self.lock() {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("failed to lock download cache, not using it: {}", e);
+                return None;
+            }
+        };
+        let mut index = self.read_index();
+        let matches: Vec<usize> = (0..index.archives.len())
+            .filter(|&i| match source_ref {
+                SourceRef::Commit(commit) => index.archives[i].commit.starts_with(commit.as_str()),
+                SourceRef::Tag(tag) => index.archives[i].tags.contains(tag),
+                SourceRef::Branch(_) => false,
+            })
+            .collect();
+        if matches.len() > 1 {
+            eprintln!(
+                "{} matches several cached archives, not using cache",
+                source_ref
+            );
+            return None;
+        }
+        let pos = *matches.first()?;
+
+        let path = self.location.join(&index.archives[pos].file_name);
+        match helper_sha256(&path) {
+            Ok((sha256, _)) if sha256 == index.archives[pos].sha256 => {}
+            Ok(_) | Err(_) => {
+                eprintln!("cached archive {:?} is corrupted, dropping it", path);
+                index.archives.remove(pos);
+                if path.exists() {
+                    fs::remove_file(&path).ok();
+                }
+                if let Err(e) = self.write_index(&index) {
+                    eprintln!("failed to update download cache index: {}", e);
+                }
+                return None;
+            }
+        }
+
+        index.archives[pos].last_used = chrono::Utc::now().timestamp();
+        if let Err(e) = self.write_index(&index) {
+            eprintln!("failed to update download cache index: {}", e);
+        }
+        println!("found {} in download cache: {:?}", source_ref, path);
+        Some(path)
+    }
+
+    ///
+    /// put a copy of given archive of given commit into cache
+    ///
+    /// if source_ref is a tag - it is remembered to point to this commit
+    /// after that least recently used archives are evicted until cache fits into size limit,
+    /// the one just stored is never evicted
+    ///
+    pub(crate) fn store(
+        &self,
+        archive: &Path,
+        commit: &str,
+        source_ref: Option<&SourceRef>,
+    ) -> Result<(), Error> {
+        fs::create_dir_all(&self.location)?;
+        let _lock = self.lock()?;
+        let mut index = self.read_index();
+
+        let file_name = format!("{}.zip", commit);
+        let cached_path = self.location.join(&file_name);
+        let is_same_file = match (archive.canonicalize(), cached_path.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        };
+        if !is_same_file {
+            // copy under temporary name first, so that no one sees a partial archive
+            let temp_path = self.location.join(helper_temp_name(&file_name));
+            fs::copy(archive, &temp_path)?;
+            fs::rename(&temp_path, &cached_path)?;
+        }
+        let (sha256, size) = helper_sha256(&cached_path)?;
+
+        let mut entry = match index.archives.iter().position(|x| x.commit == commit) {
+            Some(pos) => index.archives.remove(pos),
+            None => CachedArchive {
+                commit: commit.to_owned(),
+                file_name,
+                sha256: String::new(),
+                size: 0,
+                last_used: 0,
+                tags: Vec::new(),
+            },
+        };
+        entry.sha256 = sha256;
+        entry.size = size;
+        entry.last_used = chrono::Utc::now().timestamp();
+        if let Some(SourceRef::Tag(tag)) = source_ref {
+            // tag might have been moved from another commit
+            for other in index.archives.iter_mut() {
+                other.tags.retain(|x| x != tag);
+            }
+            if !entry.tags.contains(tag) {
+                entry.tags.push(tag.to_owned());
+            }
+        }
+
+        self.evict(&mut index, self.size_limit.saturating_sub(entry.size));
+        index.archives.push(entry);
+
+        self.write_index(&index)
+    }
+
+    ///
+    /// remove least recently used archives from index and disk
+    /// until their total size fits into given limit
+    ///
+    fn evict(&self, index: &mut CacheIndex, size_limit: u64) {
+        // forget archives that are not there any more
+        index
+            .archives
+            .retain(|x| self.location.join(&x.file_name).exists());
+        index.archives.sort_by_key(|x| x.last_used);
+
+        let mut total_size: u64 = index.archives.iter().map(|x| x.size).sum();
+        while total_size > size_limit && !index.archives.is_empty() {
+            let evicted = index.archives.remove(0);
+            println!("evicting {} from download cache", evicted.commit);
+            if let Err(e) = fs::remove_file(self.location.join(&evicted.file_name)) {
+                eprintln!("failed to remove cached archive: {}", e);
+            }
+            total_size -= evicted.size;
+        }
+    }
+
+    ///
+    /// index is always replaced in one go, so it can be read without the lock,
+    /// but it must be locked if it's going to be updated
+    ///
+    fn read_index(&self) -> CacheIndex {
+        let index_path = self.location.join(INDEX_FILE_NAME);
+        if !index_path.exists() {
+            return CacheIndex::default();
+        }
+        match fs::read_to_string(&index_path) {
+            Ok(text) => match toml::from_str(&text) {
+                Ok(x) => x,
+                Err(e) => {
+                    eprintln!("download cache index is broken, starting anew: {}", e);
+                    CacheIndex::default()
+                }
+            },
+            Err(e) => {
+                eprintln!("failed to read download cache index: {}", e);
+                CacheIndex::default()
+            }
+        }
+    }
+
+    fn write_index(&self, index: &CacheIndex) -> Result<(), Error> {
+        let text = match toml::to_string_pretty(index) {
+            Ok(x) => x,
+            Err(e) => {
+                return Err(Error::new(io::ErrorKind::InvalidData, e));
+            }
+        };
+        // other machines may read index at the same time, so replace it in one go
+        let temp_path = self.location.join(helper_temp_name(INDEX_FILE_NAME));
+        fs::write(&temp_path, text)?;
+        fs::rename(&temp_path, self.location.join(INDEX_FILE_NAME))
+    }
+}
+
+///
+/// sha256 hex digest and size of the given file
+///
+fn helper_sha256(path: &Path) -> Result<(String, u64), Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut reader, &mut hasher)?;
+    Ok((format!("{:x}", hasher.finalize()), size))
+}
+
+///
+/// temporary name for a file being written,
+/// unique enough for several machines to write into the same cache
+///
+fn helper_temp_name(file_name: &str) -> String {
+    format!("{}.{:08x}.part", file_name, thread_rng().gen::<u32>())
+}
@@ -0,0 +1,179 @@
+use crate::SourceRef;
+use downloader::Downloader;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::io::Error;
+use std::path::Path;
+use std::time::Duration;
+
+///
+/// where lifeblood sources and tools are downloaded from
+///
+/// by default it's the main github repository,
+/// but it may be a fork, or any http server that mimics github's url layout:
+///  - <base_url>/archive/refs/heads/<branch>.zip
+///  - <base_url>/archive/refs/tags/<tag>.zip
+///  - <base_url>/archive/<commit>.zip
+///  - <base_url>/releases/latest/download/houdini.zip
+///
+/// where base_url is base_url_template with {owner} and {repo} substituted
+///
+/// head commit of a ref is looked up at commit_info_url_template
+/// with {owner}, {repo} and {ref} substituted,
+/// it is expected to answer with json the way github api does:
+/// {"sha": "<commit>", "commit": {"committer": {"date": "<rfc3339 date>"}}}
+/// a plain mirror without such api has it empty
+///
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct DownloadSource {
+    pub base_url_template: String,
+    pub owner: String,
+    pub repo: String,
+    pub commit_info_url_template: String,
+    /// extra header to send with every request, in a form of "Name: value"
+    pub auth_header: Option<String>,
+}
+
+impl Default for DownloadSource {
+    fn default() -> Self {
+        DownloadSource {
+            base_url_template: "https://github.com/{owner}/{repo}".to_owned(),
+            owner: "pedohorse".to_owned(),
+            repo: "lifeblood".to_owned(),
+            commit_info_url_template: "https://api.github.com/repos/{owner}/{repo}/commits/{ref}"
+                .to_owned(),
+            auth_header: None,
+        }
+    }
+}
+
+impl DownloadSource {
+    ///
+    /// base url with all substitutions done
+    ///
+    pub fn base_url(&self) -> String {
+        self.base_url_template
+            .replace("{owner}", &self.owner)
+            .replace("{repo}", &self.repo)
+            .trim_end_matches('/')
+            .to_owned()
+    }
+
+    ///
+    /// url of source archive of given ref
+    ///
+    pub fn archive_url(&self, source_ref: &SourceRef) -> String {
+        format!("{}/archive/{}.zip", self.base_url(), source_ref.archive_path())
+    }
+
+    ///
+    /// url of the head commit information of given ref
+    ///
+    pub fn commit_info_url(&self, source_ref: &SourceRef) -> String {
+        self.commit_info_url_template
+            .replace("{owner}", &self.owner)
+            .replace("{repo}", &self.repo)
+            .replace("{ref}", source_ref.name())
+    }
+
+    ///
+    /// if head commit of a ref can be looked up at all
+    ///
+    pub fn has_commit_info(&self) -> bool {
+        !self.commit_info_url_template.trim().is_empty()
+    }
+
+    ///
+    /// url of latest released houdini tools archive
+    ///
+    pub fn tools_url(&self) -> String {
+        format!("{}/releases/latest/download/houdini.zip", self.base_url())
+    }
+
+    ///
+    /// create a downloader that sends auth header, if it's set
+    ///
+    pub(crate) fn build_downloader(
+        &self,
+        download_folder: &Path,
+        connect_timeout: Duration,
+        timeout: Duration,
+        retries: u16,
+    ) -> Result<Downloader, Error> {
+        let mut builder = Downloader::builder();
+        builder
+            .download_folder(download_folder)
+            .connect_timeout(connect_timeout)
+            .timeout(timeout)
+            .retries(retries);
+
+        let result = if let Some(ref header) = self.auth_header {
+            let mut headers = HeaderMap::new();
+            let (name, value) = Self::parse_header(header)?;
+            headers.insert(name, value);
+            let client = match reqwest::Client::builder()
+                .user_agent(format!(
+                    "{}/{}",
+                    env!("CARGO_PKG_NAME"),
+                    env!("CARGO_PKG_VERSION")
+                ))
+                .connect_timeout(connect_timeout)
+                .timeout(timeout)
+                .default_headers(headers)
+                .build()
+            {
+                Ok(x) => x,
+                Err(e) => {
+                    return Err(Error::new(
+                        std::io::ErrorKind::Other,
+                        format!("could not initialize http client :( {}", e),
+                    ));
+                }
+            };
+            builder.build_with_client(client)
+        } else {
+            builder.build()
+        };
+
+        match result {
+            Ok(x) => Ok(x),
+            Err(e) => Err(Error::new(
+                std::io::ErrorKind::Other,
+                format!("could not initialize downloader :( {}", e),
+            )),
+        }
+    }
+
+    fn parse_header(header: &str) -> Result<(HeaderName, HeaderValue), Error> {
+        let (name, value) = match header.split_once(':') {
+            Some(x) => x,
+            None => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "auth header is expected to be in a form of \"Name: value\"",
+                ));
+            }
+        };
+        let name = match HeaderName::from_bytes(name.trim().as_bytes()) {
+            Ok(x) => x,
+            Err(e) => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("bad auth header name: {}", e),
+                ));
+            }
+        };
+        let mut value = match HeaderValue::from_str(value.trim()) {
+            Ok(x) => x,
+            Err(e) => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("bad auth header value: {}", e),
+                ));
+            }
+        };
+        value.set_sensitive(true);
+
+        Ok((name, value))
+    }
+}
@@ -0,0 +1,141 @@
+use std::error;
+use std::fmt;
+use std::io::{Error, ErrorKind};
+
+///
+/// what went wrong during an installation operation,
+/// like download_new_version, install_from_zip, repair_version, add_viewer or make_version_current
+///
+/// errors of the underlying operations are kept as sources
+///
+#[derive(Debug)]
+pub enum InstallError {
+    /// archive could not be downloaded, or its commit could not be resolved
+    Download(Error),
+    /// archive could not be unpacked, or it's not a lifeblood branch archive
+    Unpack(Error),
+    /// requirements or supported python versions could not be read or written
+    Requirements(Error),
+    /// python to use is not supported by the version being installed,
+    /// or its version could not be found out
+    UnsupportedPython {
+        /// version of given python, if it could be found out
+        version: Option<String>,
+        /// python_requires of the version being installed, like ">=3.8,<3.13"
+        requires: String,
+    },
+    /// venv could not be created
+    Venv(Error),
+    /// pip failed to run or to install requirements
+    Pip(Error),
+    /// current link or launcher scripts could not be made
+    Link(Error),
+    /// version metadata could not be saved
+    Metadata(Error),
+    /// temporary files or partial installation could not be cleaned up
+    Cleanup(Error),
+    /// base path is locked by another manager, or lock could not be taken
+    Locked(Error),
+    /// operation was cancelled
+    Cancelled,
+    /// anything else, like copying files or a bad version index
+    Other(Error),
+}
+
+impl InstallError {
+    ///
+    /// io error kind, same as the one this error is turned into
+    ///
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            InstallError::UnsupportedPython { .. } => ErrorKind::Unsupported,
+            InstallError::Cancelled => ErrorKind::Interrupted,
+            InstallError::Download(e)
+            | InstallError::Unpack(e)
+            | InstallError::Requirements(e)
+            | InstallError::Venv(e)
+            | InstallError::Pip(e)
+            | InstallError::Link(e)
+            | InstallError::Metadata(e)
+            | InstallError::Cleanup(e)
+            | InstallError::Locked(e)
+            | InstallError::Other(e) => e.kind(),
+        }
+    }
+}
+
+///
+/// helper to tell which phase an io error happened in
+///
+pub(crate) trait InstallPhaseResult<T> {
+    ///
+    /// wrap error into given variant
+    ///
+    /// interrupted errors are wrapped too, as they may come from the os and not from
+    /// cancellation, operation that knows its cancellation token tells them apart
+    ///
+    fn phase(self, variant: fn(Error) -> InstallError) -> Result<T, InstallError>;
+}
+
+impl<T> InstallPhaseResult<T> for Result<T, Error> {
+    fn phase(self, variant: fn(Error) -> InstallError) -> Result<T, InstallError> {
+        self.map_err(variant)
+    }
+}
+
+impl fmt::Display for InstallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstallError::Download(e) => write!(f, "download phase: failed: {}", e),
+            InstallError::Unpack(e) => write!(f, "unpack phase: failed: {}", e),
+            InstallError::Requirements(e) => write!(f, "getting reqs failed: {}", e),
+            InstallError::UnsupportedPython { version, requires } => write!(
+                f,
+                "given python version ({}) is not supported. supported: {}",
+                version.as_deref().unwrap_or("unknown"),
+                requires
+            ),
+            InstallError::Venv(e) => write!(f, "creating venv failed: {}", e),
+            InstallError::Pip(e) => write!(f, "installing to venv failed: {}", e),
+            InstallError::Link(e) => write!(f, "create 'current' link failed: {}", e),
+            InstallError::Metadata(e) => write!(f, "saving metadata failed: {}", e),
+            InstallError::Cleanup(e) => write!(f, "cleanup phase: failed: {}", e),
+            InstallError::Locked(e) => write!(f, "{}", e),
+            InstallError::Cancelled => write!(f, "operation was cancelled"),
+            InstallError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for InstallError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            InstallError::UnsupportedPython { .. } | InstallError::Cancelled => None,
+            InstallError::Download(e)
+            | InstallError::Unpack(e)
+            | InstallError::Requirements(e)
+            | InstallError::Venv(e)
+            | InstallError::Pip(e)
+            | InstallError::Link(e)
+            | InstallError::Metadata(e)
+            | InstallError::Cleanup(e)
+            | InstallError::Locked(e)
+            | InstallError::Other(e) => Some(e),
+        }
+    }
+}
+
+impl From<Error> for InstallError {
+    fn from(e: Error) -> Self {
+        InstallError::Other(e)
+    }
+}
+
+///
+/// for code that only deals with io errors, the InstallError is kept inside
+///
+impl From<InstallError> for Error {
+    fn from(e: InstallError) -> Self {
+        Error::new(e.kind(), e)
+    }
+}
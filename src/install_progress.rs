@@ -0,0 +1,135 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+///
+/// phases of a long installation operation, in the order they happen
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallPhase {
+    Download,
+    Unpack,
+    CopyModules,
+    CreateVenv,
+    InstallRequirements,
+    CollectWheels,
+    Pack,
+    Cleanup,
+}
+
+///
+/// something that happened during a long installation operation,
+/// like download_new_version, install_from_zip, repair_version, add_viewer,
+/// export_version or import_bundle
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstallEvent {
+    /// new phase started
+    Phase(InstallPhase),
+    /// bytes downloaded so far, and total size if server told it
+    Download { downloaded: u64, total: Option<u64> },
+    /// files unpacked so far out of total number of files in the archive
+    Unpack { done: usize, total: usize },
+    /// a line of output of pip (or python running it)
+    PipOutput(String),
+    /// operation is over, with index of the version it was about, or error message
+    Finished(Result<usize, String>),
+}
+
+///
+/// callback receiving installation events
+///
+/// it is called from whatever thread the installation runs in,
+/// so it's expected to be quick, like sending the event into a channel
+///
+pub type ProgressCallback = Arc<dyn Fn(&InstallEvent) + Send + Sync>;
+
+///
+/// what installation code uses to report events, does nothing if no callback is set
+///
+#[derive(Clone, Default)]
+pub(crate) struct ProgressReporter {
+    callback: Option<ProgressCallback>,
+}
+
+impl ProgressReporter {
+    pub fn new(callback: Option<ProgressCallback>) -> ProgressReporter {
+        ProgressReporter { callback }
+    }
+
+    ///
+    /// true if anyone listens, so it's worth capturing output to report it
+    ///
+    pub fn is_enabled(&self) -> bool {
+        self.callback.is_some()
+    }
+
+    pub fn report(&self, event: InstallEvent) {
+        if let Some(callback) = &self.callback {
+            callback(&event);
+        }
+    }
+
+    pub fn phase(&self, phase: InstallPhase) {
+        self.report(InstallEvent::Phase(phase));
+    }
+
+    ///
+    /// adapter for downloader crate's progress reporting
+    ///
+    pub fn download_reporter(&self) -> downloader::progress::Progress {
+        Arc::new(DownloadReporter {
+            reporter: self.clone(),
+            total: AtomicU64::new(0),
+        })
+    }
+}
+
+struct DownloadReporter {
+    reporter: ProgressReporter,
+    total: AtomicU64, // 0 means unknown
+}
+
+impl downloader::progress::Reporter for DownloadReporter {
+    fn setup(&self, max_progress: Option<u64>, _message: &str) {
+        self.total.store(max_progress.unwrap_or(0), Ordering::Relaxed);
+        self.reporter.report(InstallEvent::Download {
+            downloaded: 0,
+            total: max_progress,
+        });
+    }
+
+    fn progress(&self, current: u64) {
+        let total = match self.total.load(Ordering::Relaxed) {
+            0 => None,
+            x => Some(x),
+        };
+        self.reporter.report(InstallEvent::Download {
+            downloaded: current,
+            total,
+        });
+    }
+
+    fn set_message(&self, _message: &str) {}
+
+    fn done(&self) {}
+}
+
+impl fmt::Display for InstallPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                InstallPhase::Download => "downloading",
+                InstallPhase::Unpack => "unpacking",
+                InstallPhase::CopyModules => "copying modules",
+                InstallPhase::CreateVenv => "creating venv",
+                InstallPhase::InstallRequirements => "installing requirements",
+                InstallPhase::CollectWheels => "collecting wheels",
+                InstallPhase::Pack => "packing",
+                InstallPhase::Cleanup => "cleaning up",
+            }
+        )
+    }
+}
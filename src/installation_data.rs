@@ -1,5 +1,4 @@
 use chrono::prelude::*;
-use semver::{Version, VersionReq};
 use core::str;
 use std::fs::File;
 use std::io::{prelude::*, BufWriter};
@@ -8,20 +7,100 @@ use std::io::{BufReader, Error};
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf, absolute};
 use std::process;
-use std::{env, fs};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::ffi::OsString;
+use std::{env, fmt, fs};
 
 use downloader::{Download, Downloader};
 
+use crate::base_dir_lock::{BaseDirLock, BASE_DIR_GENERATION_FILE_NAME, BASE_DIR_LOCK_FILE_NAME};
+use crate::cancellation_token::CancellationToken;
+use crate::download_cache::{DownloadCache, DOWNLOAD_CACHE_DIR_NAME};
+use crate::install_error::{InstallError, InstallPhaseResult};
+use crate::install_progress::{InstallEvent, InstallPhase, ProgressCallback, ProgressReporter};
+use crate::installation_helpers::get_python_command;
+use crate::installer_backend::InstallerSettings;
+use crate::package_metadata::PackageMetadata;
+use crate::python_discovery::{get_python_version, PythonDiscovery};
+use crate::python_version::{PythonVersion, VersionSpecifiers};
+use crate::running_process_data::is_version_in_use;
+use crate::version_bundle;
+use crate::version_metadata::VersionMetadata;
+use crate::version_verification::VerificationReport;
+use crate::{
+    BaseDirLockSettings, DownloadCacheSettings, DownloadSource, RetentionPolicy, BUILD_INFO,
+};
+
 use fs_extra::dir::CopyOptions;
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use zip::ZipArchive;
 
+///
+/// file in base path that remembers which version was current before the current one
+///
+const PREVIOUS_CURRENT_FILE_NAME: &str = ".previous_current";
+
+///
+/// prefix of per-host "current" links, the rest of the name is the hostname.
+/// such link overrides "current" on that host only
+///
+const HOST_CURRENT_PREFIX: &str = "current@";
+
+///
+/// prefixes of per-channel "current" link and launchers, the rest of the name is the channel
+///
+const CHANNEL_CURRENT_PREFIX: &str = "current-";
+const CHANNEL_LAUNCHER_PREFIX: &str = "lifeblood-";
+const CHANNEL_VIEWER_LAUNCHER_PREFIX: &str = "lifeblood_viewer-";
+
+///
+/// marker file in version's dir, pinned versions are never removed or pruned
+///
+const PINNED_FILE_NAME: &str = "pinned";
+
+///
+/// shortest commit hash prefix accepted, shorter ones are too likely to match several commits
+///
+const MIN_COMMIT_PREFIX_LEN: usize = 7;
+
+///
+/// length of a full commit hash, as github puts it into archive comments
+///
+const COMMIT_HASH_LEN: usize = 40;
+
+///
+/// installed version's dir is named after this many first characters of its commit hash
+///
+const NICE_NAME_LEN: usize = 13;
+
+///
+/// file in version's dir with exact versions of packages in its venv, as pip freeze gives them
+///
+const LOCK_FILE_NAME: &str = "requirements.lock";
+
 #[cfg(unix)]
 const VENV_BIN: &str = "bin";
 #[cfg(windows)]
 const VENV_BIN: &str = "Scripts";
 
+///
+/// what exactly to download from the lifeblood repository
+///
+/// a branch means whatever its head is at the moment of download,
+/// while a tag or a commit always point to the same thing
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceRef {
+    Branch(String),
+    Tag(String),
+    Commit(String),
+}
+
 #[derive(Debug)]
 pub struct InstalledVersion {
     path: PathBuf,
@@ -29,13 +108,46 @@ pub struct InstalledVersion {
     commit: String,
     date: DateTime<Utc>,
     has_viewer: bool,
+    source_ref: Option<SourceRef>,
+    channel: Option<String>,
+    pinned: bool,
+    source_url: Option<String>,
+    python: Option<PathBuf>,
+    python_version: Option<String>,
+    python_requires: Option<String>,
+    manager_build: Option<String>,
+    install_duration: Option<Duration>,
+    notes: String,
+}
+
+///
+/// result of checking a branch for updates
+///
+#[derive(Debug)]
+pub struct UpdateInfo {
+    commit: String,
+    date: DateTime<Utc>,
+    installed_version_index: Option<usize>,
 }
 
 pub struct InstallationsData {
     base_path: PathBuf,
     versions: Vec<InstalledVersion>,
     current_version: usize,
+    previous_version: usize,
+    has_host_current: bool,
     base_path_tainted: bool, // true if there is garbage unrelated to lifeblood found in the base_path
+    download_source: DownloadSource,
+    download_cache_settings: DownloadCacheSettings,
+    retention_policy: RetentionPolicy,
+    progress: ProgressReporter,
+    cancel: CancellationToken,
+    base_dir_lock_settings: BaseDirLockSettings,
+    base_dir_lock_held: Arc<AtomicBool>,
+    base_dir_generation: AtomicU64, // generation of base_path this data is up to date with
+    auto_select_python: bool,
+    installer_settings: InstallerSettings,
+    requirements_lock: Option<PathBuf>,
 }
 
 macro_rules! check_status {
@@ -56,6 +168,87 @@ macro_rules! check_status {
     };
 }
 
+impl SourceRef {
+    ///
+    /// branch, tag name or commit hash, without the kind
+    ///
+    pub fn name(&self) -> &str {
+        match self {
+            SourceRef::Branch(x) | SourceRef::Tag(x) | SourceRef::Commit(x) => x,
+        }
+    }
+
+    ///
+    /// commit given by the user may be a prefix of the hash, but not a too short one
+    ///
+    pub(crate) fn check_commit_prefix(commit: &str) -> Result<(), Error> {
+        if commit.len() < MIN_COMMIT_PREFIX_LEN || !commit.chars().all(|x| x.is_ascii_hexdigit()) {
+            return Err(Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "commit {:?} must be a hash of at least {} hex characters",
+                    commit, MIN_COMMIT_PREFIX_LEN
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    ///
+    /// part of the archive url that identifies this ref
+    ///
+    pub(crate) fn archive_path(&self) -> String {
+        match self {
+            SourceRef::Branch(x) => format!("refs/heads/{}", x),
+            SourceRef::Tag(x) => format!("refs/tags/{}", x),
+            SourceRef::Commit(x) => x.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for SourceRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceRef::Branch(x) => write!(f, "branch:{}", x),
+            SourceRef::Tag(x) => write!(f, "tag:{}", x),
+            SourceRef::Commit(x) => write!(f, "commit:{}", x),
+        }
+    }
+}
+
+impl FromStr for SourceRef {
+    type Err = Error;
+
+    ///
+    /// parse what Display produces,
+    /// a string without a kind prefix is treated as a branch name
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, name) = match s.split_once(':') {
+            Some((kind, name)) => (kind, name),
+            None => ("branch", s),
+        };
+        if name.is_empty() {
+            return Err(Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("empty ref name in {:?}", s),
+            ));
+        }
+        match kind {
+            "branch" => Ok(SourceRef::Branch(name.to_owned())),
+            "tag" => Ok(SourceRef::Tag(name.to_owned())),
+            "commit" => {
+                Self::check_commit_prefix(name)?;
+                Ok(SourceRef::Commit(name.to_owned()))
+            }
+            x => Err(Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("unknown ref kind {:?}, expected branch, tag or commit", x),
+            )),
+        }
+    }
+}
+
 impl InstalledVersion {
     ///
     /// given path construct a version object that is contained in it
@@ -101,30 +294,93 @@ impl InstalledVersion {
         }
 
         // try read metadata
-        let (commit, date) = match helper_read_metadata(&path.join("meta.info")) {
-            Ok((_, commit, date)) => (commit, date),
+        let metadata = match VersionMetadata::read_from_file(&path.join("meta.info")) {
+            Ok(x) => x,
             Err(_) => {
                 eprintln!("failed to read date from metadata, using dir name and creation time");
-                ("unknown".to_owned(), path.metadata()?.created()?.into())
+                VersionMetadata {
+                    commit: "unknown".to_owned(),
+                    date: path.metadata()?.created()?.into(),
+                    ..VersionMetadata::default()
+                }
             }
         };
 
+        Ok(Self::from_metadata(path, file_name, metadata))
+    }
+
+    ///
+    /// construct version object from metadata, the rest is checked on disk
+    ///
+    fn from_metadata(
+        path: PathBuf,
+        nice_name: String,
+        metadata: VersionMetadata,
+    ) -> InstalledVersion {
         // check viewer
         let has_viewer = path.join("lifeblood_viewer").exists();
 
-        Ok(InstalledVersion {
+        let pinned = path.join(PINNED_FILE_NAME).exists();
+
+        // versions installed before channels were saved belong to the branch they came from
+        let channel = metadata.channel.or_else(|| match &metadata.source_ref {
+            Some(SourceRef::Branch(x)) => Some(x.clone()),
+            _ => None,
+        });
+
+        InstalledVersion {
             path,
-            nice_name: file_name,
-            commit,
-            date,
+            nice_name,
+            commit: metadata.commit,
+            date: metadata.date,
             has_viewer,
-        })
+            source_ref: metadata.source_ref,
+            channel,
+            pinned,
+            source_url: metadata.source_url,
+            python: metadata.python,
+            python_version: metadata.python_version,
+            python_requires: metadata.python_requires,
+            manager_build: metadata.manager_build,
+            install_duration: metadata
+                .install_duration_secs
+                .and_then(|x| Duration::try_from_secs_f64(x).ok()),
+            notes: metadata.notes,
+        }
+    }
+
+    ///
+    /// metadata as it should be saved for this version
+    ///
+    fn metadata(&self) -> VersionMetadata {
+        VersionMetadata {
+            nice_name: self.nice_name.clone(),
+            commit: self.commit.clone(),
+            date: self.date,
+            source_url: self.source_url.clone(),
+            source_ref: self.source_ref.clone(),
+            channel: self.channel.clone(),
+            python: self.python.clone(),
+            python_version: self.python_version.clone(),
+            python_requires: self.python_requires.clone(),
+            has_viewer: Some(self.has_viewer),
+            manager_build: self.manager_build.clone(),
+            install_duration_secs: self.install_duration.map(|x| x.as_secs_f64()),
+            notes: self.notes.clone(),
+        }
     }
 
     pub fn nice_name(&self) -> &str {
         &self.nice_name
     }
 
+    ///
+    /// directory this version is installed into
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn source_commit(&self) -> &str {
         &self.commit
     }
@@ -133,10 +389,85 @@ impl InstalledVersion {
         self.has_viewer
     }
 
+    ///
+    /// pinned versions are protected from removal and pruning
+    ///
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    ///
+    /// branch, tag or commit this version was installed from,
+    /// None if unknown, like for versions installed from a local archive
+    ///
+    pub fn source_ref(&self) -> Option<&SourceRef> {
+        self.source_ref.as_ref()
+    }
+
+    ///
+    /// release channel this version belongs to, it's the branch version was downloaded from.
+    /// None for versions installed from a tag, a commit or a local archive
+    ///
+    pub fn channel(&self) -> Option<&str> {
+        self.channel.as_deref()
+    }
+
     pub fn date(&self) -> &DateTime<Utc> {
         &self.date
     }
 
+    ///
+    /// url of the archive this version was installed from, if known
+    ///
+    pub fn source_url(&self) -> Option<&str> {
+        self.source_url.as_deref()
+    }
+
+    ///
+    /// python interpreter that was used to create venv, if known
+    ///
+    pub fn python(&self) -> Option<&Path> {
+        self.python.as_deref()
+    }
+
+    ///
+    /// version of venv's python, like "3.11.4", if known
+    ///
+    pub fn python_version(&self) -> Option<&str> {
+        self.python_version.as_deref()
+    }
+
+    ///
+    /// BUILD_INFO of the manager that installed this version, if known
+    ///
+    pub fn manager_build(&self) -> Option<&str> {
+        self.manager_build.as_deref()
+    }
+
+    ///
+    /// how long the installation took, if known
+    ///
+    pub fn install_duration(&self) -> Option<Duration> {
+        self.install_duration
+    }
+
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+
+    ///
+    /// file with exact versions of packages installed into version's venv,
+    /// None if it was not recorded, like for versions installed by older managers
+    ///
+    pub fn lock_file(&self) -> Option<PathBuf> {
+        let path = self.path.join(LOCK_FILE_NAME);
+        if path.exists() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
     pub fn set_nice_name(&mut self, name: String) -> Result<(), Error> {
         let new_path = self.path.with_file_name(&name);
         std::fs::rename(&self.path, &new_path)?;
@@ -145,6 +476,42 @@ impl InstalledVersion {
 
         Ok(())
     }
+
+    fn set_pinned(&mut self, pinned: bool) -> Result<(), Error> {
+        let marker_path = self.path.join(PINNED_FILE_NAME);
+        if pinned {
+            fs::write(&marker_path, "")?;
+        } else if marker_path.exists() {
+            fs::remove_file(&marker_path)?;
+        }
+        self.pinned = pinned;
+
+        Ok(())
+    }
+}
+
+impl UpdateInfo {
+    ///
+    /// head commit of the checked branch
+    ///
+    pub fn latest_commit(&self) -> &str {
+        &self.commit
+    }
+
+    pub fn latest_date(&self) -> &DateTime<Utc> {
+        &self.date
+    }
+
+    ///
+    /// index of the installed version of the latest commit, if it is installed
+    ///
+    pub fn installed_version_index(&self) -> Option<usize> {
+        self.installed_version_index
+    }
+
+    pub fn is_update_available(&self) -> bool {
+        self.installed_version_index.is_none()
+    }
 }
 
 impl InstallationsData {
@@ -152,16 +519,177 @@ impl InstallationsData {
     /// rename given version, update current if needed
     /// this may fail if FS deems name bad
     pub fn rename_version(&mut self, version_id: usize, new_name: String) -> Result<(), Error> {
+        let _lock = self.lock_base_dir()?;
         if version_id >= self.versions.len() {
             return Err(Error::new(
                 std::io::ErrorKind::InvalidData,
                 "given version_id does not exist",
             ));
         }
+        let is_channel_current = self.versions[version_id]
+            .channel
+            .as_deref()
+            .is_some_and(|x| self.channel_current_version_index(x) == Some(version_id));
         self.versions[version_id].set_nice_name(new_name)?;
         if self.current_version == version_id {
             self.make_version_current(version_id)?;
         }
+        if is_channel_current {
+            self.helper_make_version_channel_current(version_id)?;
+        }
+        if self.previous_version == version_id {
+            self.save_previous_version()?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// set free-form notes of given version, they are saved into version's metadata
+    ///
+    pub fn set_version_notes(&mut self, version_id: usize, notes: String) -> Result<(), Error> {
+        let _lock = self.lock_base_dir()?;
+        let ver = match self.versions.get_mut(version_id) {
+            Some(x) => x,
+            None => {
+                return Err(Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "given version_id does not exist",
+                ));
+            }
+        };
+        let mut metadata = ver.metadata();
+        metadata.notes = notes;
+        metadata.write_to_file(&ver.path.join("meta.info"))?;
+        ver.notes = metadata.notes;
+
+        Ok(())
+    }
+
+    ///
+    /// pin given version, so it's never removed or pruned
+    ///
+    pub fn pin_version(&mut self, version_id: usize) -> Result<(), Error> {
+        let _lock = self.lock_base_dir()?;
+        match self.versions.get_mut(version_id) {
+            Some(ver) => ver.set_pinned(true),
+            None => Err(Error::new(
+                std::io::ErrorKind::NotFound,
+                "given version_id does not exist",
+            )),
+        }
+    }
+
+    ///
+    /// unpin given version
+    ///
+    pub fn unpin_version(&mut self, version_id: usize) -> Result<(), Error> {
+        let _lock = self.lock_base_dir()?;
+        match self.versions.get_mut(version_id) {
+            Some(ver) => ver.set_pinned(false),
+            None => Err(Error::new(
+                std::io::ErrorKind::NotFound,
+                "given version_id does not exist",
+            )),
+        }
+    }
+
+    ///
+    /// remove given version from disk
+    ///
+    /// current and pinned versions cannot be removed,
+    /// neither can be a version that a LaunchedProcess of this manager is still running from.
+    /// processes started by other managers or by hand cannot be detected though
+    ///
+    pub fn remove_version(&mut self, version_id: usize) -> Result<(), Error> {
+        let _lock = self.lock_base_dir()?;
+        let ver = match self.versions.get(version_id) {
+            Some(x) => x,
+            None => {
+                return Err(Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "given version_id does not exist",
+                ));
+            }
+        };
+        if version_id == self.current_version {
+            return Err(Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "cannot remove current version, make another version current first",
+            ));
+        }
+        if let Some(channel) = ver.channel.as_deref() {
+            if self.channel_current_version_index(channel) == Some(version_id) {
+                return Err(Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    format!(
+                        "cannot remove version {}, it is current of channel {}",
+                        ver.nice_name, channel
+                    ),
+                ));
+            }
+        }
+        if let Some((link_name, _)) = self
+            .helper_current_links()
+            .into_iter()
+            .find(|(_, target)| *target == ver.path)
+        {
+            return Err(Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!(
+                    "cannot remove version {}, {} points to it",
+                    ver.nice_name, link_name
+                ),
+            ));
+        }
+        if ver.pinned {
+            return Err(Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("cannot remove version {}, it is pinned", ver.nice_name),
+            ));
+        }
+        if is_version_in_use(&ver.path) {
+            return Err(Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!(
+                    "cannot remove version {}, there are processes running from it",
+                    ver.nice_name
+                ),
+            ));
+        }
+
+        // move it out of the way first, so that a half removed dir is not mistaken for a version
+        let removing_path = ver
+            .path
+            .with_file_name(format!("__removing_{}", helper_random_name()));
+        if let Err(e) = fs::rename(&ver.path, &removing_path) {
+            return Err(Error::new(
+                e.kind(),
+                format!("failed to remove version {}: {}", ver.nice_name, e),
+            ));
+        }
+        self.versions.remove(version_id);
+        if self.current_version != usize::MAX && self.current_version > version_id {
+            self.current_version -= 1;
+        }
+        if self.previous_version == version_id {
+            self.previous_version = usize::MAX;
+            if let Err(e) = self.save_previous_version() {
+                eprintln!("failed to forget previous current version: {}", e);
+            }
+        } else if self.previous_version != usize::MAX && self.previous_version > version_id {
+            self.previous_version -= 1;
+        }
+
+        if let Err(e) = fs::remove_dir_all(&removing_path) {
+            return Err(Error::new(
+                e.kind(),
+                format!(
+                    "version removed, but failed to clean up {:?}, please remove it manually: {}",
+                    removing_path, e
+                ),
+            ));
+        }
 
         Ok(())
     }
@@ -179,7 +707,9 @@ impl InstallationsData {
     pub fn from_dir(base_path: PathBuf) -> Result<InstallationsData, Error> {
         let mut versions = Vec::new();
         let mut current_version = usize::MAX;
+        let mut previous_version = usize::MAX;
         let mut current_path = PathBuf::new();
+        let mut host_current_path = None;
         let mut base_path_tainted = false;
 
         let base_path = if let Ok(x) = absolute(base_path) {
@@ -187,6 +717,10 @@ impl InstallationsData {
         } else {
             return Err(Error::new(std::io::ErrorKind::InvalidData, "bad base path"));
         };
+        // taken before scanning, so changes made during the scan are not missed
+        let base_dir_generation = BaseDirLock::generation(&base_path);
+
+        let host_link_name = host_current_link_name();
 
         let maybe_me = if let Ok(p) = env::current_exe() {
             Some(if let Ok(cp) = absolute(&p) { cp } else { p })
@@ -208,6 +742,26 @@ impl InstallationsData {
 
                     let path = entry.path();
                     match path {
+                        // case it's some host's own 'current' link
+                        path if path.is_symlink()
+                            && entry
+                                .file_name()
+                                .to_string_lossy()
+                                .starts_with(HOST_CURRENT_PREFIX) =>
+                        {
+                            if host_link_name.as_ref().is_some_and(|x| path.ends_with(x)) {
+                                host_current_path = path.read_link().ok();
+                            }
+                        }
+                        // case it's some channel's 'current' link
+                        path if path.is_symlink()
+                            && entry
+                                .file_name()
+                                .to_string_lossy()
+                                .starts_with(CHANNEL_CURRENT_PREFIX) =>
+                        {
+                            continue;
+                        }
                         // case it's 'current' link
                         path if path.is_symlink() && path.ends_with("current") => {
                             match path.read_link() {
@@ -235,6 +789,10 @@ impl InstallationsData {
                                 }
                             }
                         }
+                        // case it's download cache
+                        path if path.is_dir() && path.ends_with(DOWNLOAD_CACHE_DIR_NAME) => {
+                            continue;
+                        }
                         // case it's a dir
                         path if path.is_dir() => match InstalledVersion::from_path(path) {
                             Ok(info) => {
@@ -271,6 +829,20 @@ impl InstallationsData {
                                 && !path.ends_with("lifeblood_viewer")
                                 && !path.ends_with("lifeblood_viewer.cmd")
                                 && !path.ends_with("lifeblood-manager.config")
+                                && !entry
+                                    .file_name()
+                                    .to_string_lossy()
+                                    .starts_with(PREVIOUS_CURRENT_FILE_NAME)
+                                && !entry
+                                    .file_name()
+                                    .to_string_lossy()
+                                    .starts_with(CHANNEL_LAUNCHER_PREFIX)
+                                && !entry
+                                    .file_name()
+                                    .to_string_lossy()
+                                    .starts_with(CHANNEL_VIEWER_LAUNCHER_PREFIX)
+                                && !path.ends_with(BASE_DIR_LOCK_FILE_NAME)
+                                && !path.ends_with(BASE_DIR_GENERATION_FILE_NAME)
                             {
                                 base_path_tainted = true;
                                 println!("skipping {:?}", path);
@@ -283,6 +855,21 @@ impl InstallationsData {
             Err(e) => return Err(e),
         }
 
+        // this host's link takes over, unless it's broken
+        let mut has_host_current = false;
+        if let Some(host_path) = host_current_path.map(|x| base_path.join(x)) {
+            if versions.iter().any(|x| x.path == host_path) {
+                println!("current is overridden for this host");
+                current_path = host_path;
+                has_host_current = true;
+            } else {
+                eprintln!(
+                    "this host's current link points to {:?} that is not a version, ignoring it",
+                    host_path
+                );
+            }
+        }
+
         println!("curr path {:?}", current_path);
         for (i, ver) in versions.iter().enumerate() {
             if ver.path == current_path {
@@ -290,12 +877,32 @@ impl InstallationsData {
                 println!("curr {}", current_version);
             }
         }
+        let previous_file_name = Self::helper_previous_current_file_name(has_host_current);
+        if let Ok(previous_name) = fs::read_to_string(base_path.join(previous_file_name)) {
+            let previous_path = base_path.join(previous_name.trim());
+            if let Some(i) = versions.iter().position(|x| x.path == previous_path) {
+                previous_version = i;
+            }
+        }
 
         Ok(InstallationsData {
             base_path,
             versions,
             current_version,
+            previous_version,
+            has_host_current,
             base_path_tainted,
+            download_source: DownloadSource::default(),
+            download_cache_settings: DownloadCacheSettings::default(),
+            retention_policy: RetentionPolicy::default(),
+            progress: ProgressReporter::default(),
+            cancel: CancellationToken::default(),
+            base_dir_lock_settings: BaseDirLockSettings::default(),
+            base_dir_lock_held: Arc::new(AtomicBool::new(false)),
+            base_dir_generation: AtomicU64::new(base_dir_generation),
+            auto_select_python: false,
+            installer_settings: InstallerSettings::default(),
+            requirements_lock: None,
         })
     }
 
@@ -314,68 +921,1464 @@ impl InstallationsData {
     }
 
     ///
-    /// get version from index
+    /// where new versions are downloaded from
+    ///
+    pub fn download_source(&self) -> &DownloadSource {
+        &self.download_source
+    }
+
+    ///
+    /// set where new versions are downloaded from
+    /// by default it's the main lifeblood github repository
+    ///
+    pub fn set_download_source(&mut self, download_source: DownloadSource) {
+        self.download_source = download_source;
+    }
+
+    ///
+    /// settings of the downloaded archives cache
+    ///
+    pub fn download_cache_settings(&self) -> &DownloadCacheSettings {
+        &self.download_cache_settings
+    }
+
+    ///
+    /// set download cache settings
+    /// by default cache is enabled and located inside base_path
+    ///
+    pub fn set_download_cache_settings(&mut self, settings: DownloadCacheSettings) {
+        self.download_cache_settings = settings;
+    }
+
+    ///
+    /// policy of which versions to keep when pruning
+    ///
+    pub fn retention_policy(&self) -> &RetentionPolicy {
+        &self.retention_policy
+    }
+
+    ///
+    /// set retention policy
+    /// if it's enabled - it's also applied after every successful download_new_version
+    ///
+    pub fn set_retention_policy(&mut self, policy: RetentionPolicy) {
+        self.retention_policy = policy;
+    }
+
+    ///
+    /// set callback to receive progress events of long operations,
+    /// like download_new_version, install_from_zip, repair_version and add_viewer
+    ///
+    /// while callback is set - pip output is not printed, but reported to the callback
+    ///
+    pub fn set_progress_callback(&mut self, callback: Option<ProgressCallback>) {
+        self.progress = ProgressReporter::new(callback);
+    }
+
+    ///
+    /// set token to cancel long operations,
+    /// like download_new_version, install_from_zip, repair_version and add_viewer
+    ///
+    /// cancellation is checked between installation phases, running pip is killed,
+    /// and whatever was done by the cancelled operation is rolled back
+    ///
+    pub fn set_cancellation_token(&mut self, token: Option<CancellationToken>) {
+        self.cancel = token.unwrap_or_default();
+    }
+
+    ///
+    /// if installation operations look for a suitable python themselves
+    /// when no python is given to them
+    ///
+    pub fn auto_select_python(&self) -> bool {
+        self.auto_select_python
+    }
+
+    ///
+    /// when set and no python is given to installation operations - the newest
+    /// of pythons found on this machine that the version being installed supports is used.
+    /// if none is found - installation fails with UnsupportedPython,
+    /// except on windows, where embedded python is used as without discovery
+    ///
+    pub fn set_auto_select_python(&mut self, auto_select: bool) {
+        self.auto_select_python = auto_select;
+    }
+
+    ///
+    /// what creates venvs and installs requirements of new versions
+    ///
+    pub fn installer_settings(&self) -> &InstallerSettings {
+        &self.installer_settings
+    }
+
+    ///
+    /// choose installer for venvs, by default uv is used if it's found in PATH, pip otherwise,
+    /// and where it gets packages from, by default from pypi
+    ///
+    /// relative wheelhouse is taken relative to base_path
+    ///
+    pub fn set_installer_settings(&mut self, mut settings: InstallerSettings) {
+        if let Some(wheelhouse) = &settings.wheelhouse {
+            settings.wheelhouse = Some(self.base_path.join(wheelhouse));
+        }
+        self.installer_settings = settings;
+    }
+
+    ///
+    /// lock file new versions are installed according to, if any
+    ///
+    pub fn requirements_lock(&self) -> Option<&Path> {
+        self.requirements_lock.as_deref()
+    }
+
+    ///
+    /// make new versions install exactly the package versions listed in given lock file,
+    /// like the lock_file of a version installed on another machine,
+    /// packages not listed there are resolved as usual
+    ///
+    /// already installed commits are not reinstalled because of that
+    ///
+    pub fn set_requirements_lock(&mut self, lock_file: Option<PathBuf>) {
+        self.requirements_lock = lock_file;
+    }
+
+    ///
+    /// what to do if base_path is locked by another manager
+    ///
+    pub fn base_dir_lock_settings(&self) -> &BaseDirLockSettings {
+        &self.base_dir_lock_settings
+    }
+
+    ///
+    /// set what to do if base_path is locked by another manager
+    /// by default operations fail right away
+    ///
+    pub fn set_base_dir_lock_settings(&mut self, settings: BaseDirLockSettings) {
+        self.base_dir_lock_settings = settings;
+    }
+
+    ///
+    /// take base_path lock for an operation that changes anything in base_path
+    ///
+    /// lock is held until returned value is dropped,
+    /// operations nested in another locked operation get None, as lock is already held
+    ///
+    /// another manager may have changed base_path since it was scanned,
+    /// then version indices given by the caller may point to wrong versions,
+    /// so in that case lock is not taken and base_path has to be reloaded.
+    /// every locked operation bumps base_path generation, so it's enough to compare that
+    ///
+    fn lock_base_dir(&self) -> Result<Option<BaseDirLock>, Error> {
+        let lock = BaseDirLock::acquire(
+            &self.base_path,
+            &self.base_dir_lock_settings,
+            &self.base_dir_lock_held,
+            &self.cancel,
+        )?;
+        if let Some(ref lock) = lock {
+            if BaseDirLock::generation(&self.base_path)
+                != self.base_dir_generation.load(Ordering::SeqCst)
+            {
+                return Err(Error::new(
+                    std::io::ErrorKind::Other,
+                    format!(
+                        "base path {:?} was changed by another manager, reload it",
+                        self.base_path
+                    ),
+                ));
+            }
+            self.base_dir_generation
+                .store(lock.bump_generation()?, Ordering::SeqCst);
+        }
+        Ok(lock)
+    }
+
+    ///
+    /// interrupted error of cancelled operation is a cancellation,
+    /// but operation that was not cancelled may be interrupted by the os as well
+    ///
+    fn check_cancelled<T>(&self, result: Result<T, InstallError>) -> Result<T, InstallError> {
+        match result {
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted && self.cancel.is_cancelled() => {
+                Err(InstallError::Cancelled)
+            }
+            x => x,
+        }
+    }
+
+    fn report_finished(&self, result: Result<usize, &InstallError>) {
+        self.progress
+            .report(InstallEvent::Finished(result.map_err(|e| e.to_string())));
+    }
+
+    ///
+    /// download cache, if enabled
+    ///
+    fn download_cache(&self) -> Option<DownloadCache> {
+        if !self.download_cache_settings.enabled {
+            return None;
+        }
+        let location = match self.download_cache_settings.location {
+            Some(ref x) => x.clone(),
+            None => self.base_path.join(DOWNLOAD_CACHE_DIR_NAME),
+        };
+        // cache right in base_path has the same lock, that may be already held by the operation
+        let is_base_path = location == self.base_path
+            || location
+                .canonicalize()
+                .is_ok_and(|x| self.base_path.canonicalize().is_ok_and(|y| x == y));
+        Some(DownloadCache::new(
+            location,
+            self.download_cache_settings.size_limit_mb,
+            self.cancel.clone(),
+            is_base_path.then(|| self.base_dir_lock_held.clone()),
+        ))
+    }
+
+    ///
+    /// get version from index
     ///
     pub fn version(&self, i: usize) -> Option<&InstalledVersion> {
         self.versions.get(i)
     }
 
     ///
-    /// current version if set
+    /// current version if set
+    ///
+    pub fn current_version(&self) -> Option<&InstalledVersion> {
+        if self.current_version == usize::MAX {
+            None
+        } else {
+            if let Some(ver) = self.versions.get(self.current_version) {
+                Some(ver)
+            } else {
+                None
+            }
+        }
+    }
+
+    ///
+    /// index of current version
+    ///
+    pub fn current_version_index(&self) -> usize {
+        self.current_version
+    }
+
+    ///
+    /// index of the version that was current before the current one,
+    /// usize::MAX if unknown
+    ///
+    pub fn previous_version_index(&self) -> usize {
+        self.previous_version
+    }
+
+    ///
+    /// release channels of installed versions, sorted
+    ///
+    pub fn channels(&self) -> Vec<String> {
+        let mut channels: Vec<String> = self
+            .versions
+            .iter()
+            .filter_map(|x| x.channel.clone())
+            .collect();
+        channels.sort();
+        channels.dedup();
+        channels
+    }
+
+    ///
+    /// index of current version of given channel, None if channel has no current version
+    ///
+    pub fn channel_current_version_index(&self, channel: &str) -> Option<usize> {
+        let path = pointed_version_path(
+            &self.base_path,
+            &format!("{}{}", CHANNEL_CURRENT_PREFIX, channel),
+            &format!("{}{}", CHANNEL_LAUNCHER_PREFIX, channel),
+        )?;
+        self.versions.iter().position(|x| x.path == path)
+    }
+
+    ///
+    /// channels whose current version is given one
+    ///
+    fn helper_version_channels(&self, i: usize) -> Vec<String> {
+        self.channels()
+            .into_iter()
+            .filter(|x| self.channel_current_version_index(x) == Some(i))
+            .collect()
+    }
+
+    ///
+    /// index of the newest installed version of given channel
+    ///
+    pub fn channel_latest_version_index(&self, channel: &str) -> Option<usize> {
+        // versions are sorted by date
+        self.versions
+            .iter()
+            .rposition(|x| x.channel.as_deref() == Some(channel))
+    }
+
+    ///
+    /// indices of versions that retention policy does not keep, in ascending order
+    ///
+    /// pinned versions and versions that are in use by running processes are kept too
+    ///
+    pub fn versions_to_prune(&self) -> Vec<usize> {
+        self.helper_versions_to_prune(usize::MAX)
+    }
+
+    ///
+    /// remove all versions that retention policy does not keep
+    ///
+    /// returns names of removed versions.
+    /// if some versions fail to be removed - the rest are still removed, and error is returned
+    ///
+    pub fn prune_versions(&mut self) -> Result<Vec<String>, Error> {
+        let _lock = self.lock_base_dir()?;
+        let to_prune = self.versions_to_prune();
+        self.helper_remove_versions(to_prune)
+    }
+
+    fn helper_versions_to_prune(&self, also_keep: usize) -> Vec<usize> {
+        let policy = &self.retention_policy;
+        if !policy.is_enabled() {
+            return Vec::new();
+        }
+        let cutoff_date = policy
+            .keep_days
+            .map(|days| Utc::now() - chrono::Duration::days(days as i64));
+        let keep_newest = policy.keep_newest.unwrap_or(0);
+        // versions other hosts have as current are kept too
+        let linked: Vec<PathBuf> = self
+            .helper_current_links()
+            .into_iter()
+            .map(|(_, x)| x)
+            .collect();
+
+        // versions are sorted by date, so the newest are at the end
+        (0..self.versions.len())
+            .filter(|&i| {
+                let ver = &self.versions[i];
+                !(i == self.current_version
+                    || i == self.previous_version
+                    || i == also_keep
+                    || linked.contains(&ver.path)
+                    || ver
+                        .channel
+                        .as_deref()
+                        .is_some_and(|x| self.channel_current_version_index(x) == Some(i))
+                    || ver.pinned
+                    || i + keep_newest >= self.versions.len()
+                    || cutoff_date.is_some_and(|cutoff| ver.date >= cutoff)
+                    || is_version_in_use(&ver.path))
+            })
+            .collect()
+    }
+
+    fn helper_remove_versions(
+        &mut self,
+        mut version_ids: Vec<usize>,
+    ) -> Result<Vec<String>, Error> {
+        let mut removed = Vec::new();
+        let mut errors = Vec::new();
+        // remove from the end, so indices of the rest stay valid
+        version_ids.sort_unstable();
+        for i in version_ids.into_iter().rev() {
+            let name = self.versions[i].nice_name.clone();
+            println!("pruning version {}", name);
+            match self.remove_version(i) {
+                Ok(_) => removed.push(name),
+                Err(e) => errors.push(format!("{}: {}", name, e)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::new(
+                std::io::ErrorKind::Other,
+                format!("failed to remove some versions: {}", errors.join("; ")),
+            ));
+        }
+        Ok(removed)
+    }
+
+    ///
+    /// remember previous current version in base path,
+    /// so it's known next time base path is scanned
+    ///
+    fn save_previous_version(&self) -> Result<(), Error> {
+        let file_path = self
+            .base_path
+            .join(Self::helper_previous_current_file_name(self.has_host_current));
+        match self.versions.get(self.previous_version) {
+            Some(ver) => fs::write(file_path, &ver.nice_name),
+            None if file_path.exists() => fs::remove_file(file_path),
+            None => Ok(()),
+        }
+    }
+
+    ///
+    /// helper func
+    ///
+    /// hosts with their own current remember their own previous current
+    ///
+    fn helper_previous_current_file_name(has_host_current: bool) -> String {
+        match host_current_link_name() {
+            Some(link_name) if has_host_current => format!(
+                "{}{}",
+                PREVIOUS_CURRENT_FILE_NAME,
+                &link_name["current".len()..]
+            ),
+            _ => PREVIOUS_CURRENT_FILE_NAME.to_owned(),
+        }
+    }
+
+    ///
+    /// helper func
+    ///
+    /// "current" and per-host current links in base path, with paths they point to
+    ///
+    fn helper_current_links(&self) -> Vec<(String, PathBuf)> {
+        let dir_iter = match fs::read_dir(&self.base_path) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("failed to list current links: {}", e);
+                return Vec::new();
+            }
+        };
+        dir_iter
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name != "current" && !name.starts_with(HOST_CURRENT_PREFIX) {
+                    return None;
+                }
+                let target = entry.path().read_link().ok()?;
+                Some((name, self.base_path.join(target)))
+            })
+            .collect()
+    }
+
+    ///
+    /// iterator over versions
+    ///
+    pub fn iter_versions(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &InstalledVersion> + ExactSizeIterator + '_ {
+        self.versions.iter()
+    }
+
+    ///
+    /// number of versions found
+    ///
+    pub fn version_count(&self) -> usize {
+        self.versions.len()
+    }
+
+    ///
+    /// set given version by index as current
+    ///
+    /// concept of "current" is implemented differently on different platforms
+    /// in any way this will cause modifications to the filesystem
+    ///
+    /// on unix - "current" link will be changed,
+    /// or this host's own current link, if it has one (see set_host_current)
+    /// on windows - lifeblood.cmd lifeblood_viewer.cmd will be changed directly
+    ///
+    pub fn make_version_current(&mut self, i: usize) -> Result<(), InstallError> {
+        let _lock = self.lock_base_dir().phase(InstallError::Locked)?;
+        if i >= self.versions.len() {
+            return Err(InstallError::Other(Error::new(
+                std::io::ErrorKind::NotFound,
+                "no such version",
+            )));
+        }
+        let prev_current = self.current_version;
+
+        #[cfg(unix)]
+        self.make_version_current_unix(i, &self.current_link_name())
+            .phase(InstallError::Link)?;
+        #[cfg(windows)]
+        self.make_version_current_win(i, false).phase(InstallError::Link)?;
+
+        self.helper_remember_previous_version(prev_current);
+        Ok(())
+    }
+
+    ///
+    /// true if this host has its own current version, different from the one
+    /// all other hosts sharing this base path use
+    ///
+    pub fn has_host_current(&self) -> bool {
+        self.has_host_current
+    }
+
+    ///
+    /// give this host its own current version, or, with None, drop it
+    /// and go back to the current version shared by all hosts
+    ///
+    /// this is useful when base path is on a network share used by many hosts,
+    /// and some host needs to try a version out, or stay on an older one.
+    /// while this host has its own current - make_version_current changes only that
+    ///
+    /// only supported on unix, as there "current" is a link
+    ///
+    pub fn set_host_current(&mut self, i: Option<usize>) -> Result<(), InstallError> {
+        let _lock = self.lock_base_dir().phase(InstallError::Locked)?;
+        #[cfg(unix)]
+        let result = self.set_host_current_unix(i);
+        #[cfg(windows)]
+        let result = Err(InstallError::Other(Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("per-host current version is only supported on unix, cannot set {:?}", i),
+        )));
+        result
+    }
+
+    #[cfg(unix)]
+    fn set_host_current_unix(&mut self, i: Option<usize>) -> Result<(), InstallError> {
+        let link_name = match host_current_link_name() {
+            Some(x) => x,
+            None => {
+                return Err(InstallError::Other(Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "failed to get hostname",
+                )));
+            }
+        };
+        let prev_current = self.current_version;
+        match i {
+            Some(i) => {
+                if i >= self.versions.len() {
+                    return Err(InstallError::Other(Error::new(
+                        std::io::ErrorKind::NotFound,
+                        "no such version",
+                    )));
+                }
+                self.make_version_current_unix(i, &link_name)
+                    .phase(InstallError::Link)?;
+                if !self.has_host_current {
+                    // previous current is remembered per host, so start it anew
+                    self.has_host_current = true;
+                    self.previous_version = usize::MAX;
+                }
+                self.current_version = i;
+            }
+            None => {
+                let link_path = self.base_path.join(&link_name);
+                if link_path.is_symlink() {
+                    fs::remove_file(&link_path).phase(InstallError::Link)?;
+                }
+                if self.has_host_current {
+                    self.has_host_current = false;
+                    self.previous_version = usize::MAX;
+                }
+                self.current_version = current_version_path(&self.base_path)
+                    .and_then(|path| self.versions.iter().position(|x| x.path == path))
+                    .unwrap_or(usize::MAX);
+            }
+        }
+        self.helper_remember_previous_version(prev_current);
+        Ok(())
+    }
+
+    ///
+    /// helper func
+    ///
+    /// name of the link that current version is changed through
+    ///
+    #[cfg(unix)]
+    fn current_link_name(&self) -> String {
+        match host_current_link_name() {
+            Some(link_name) if self.has_host_current => link_name,
+            _ => "current".to_owned(),
+        }
+    }
+
+    ///
+    /// helper func
+    ///
+    /// remember version that was current before, if current has changed
+    ///
+    fn helper_remember_previous_version(&mut self, prev_current: usize) {
+        if prev_current != usize::MAX && prev_current != self.current_version {
+            self.previous_version = prev_current;
+            if let Err(e) = self.save_previous_version() {
+                eprintln!("failed to remember previous current version: {}", e);
+            }
+        }
+    }
+
+    ///
+    /// set given version by index as current of its channel
+    ///
+    /// each channel has its own "current-<channel>" link on unix
+    /// and its own launchers: lifeblood-<channel> and lifeblood_viewer-<channel>,
+    /// so several channels can be used side by side from the same base path.
+    /// "current" itself is not affected
+    ///
+    pub fn make_version_channel_current(&mut self, i: usize) -> Result<(), InstallError> {
+        let _lock = self.lock_base_dir().phase(InstallError::Locked)?;
+        self.helper_make_version_channel_current(i)
+            .phase(InstallError::Link)
+    }
+
+    fn helper_make_version_channel_current(&mut self, i: usize) -> Result<(), Error> {
+        let channel = match self.versions.get(i) {
+            Some(ver) => match &ver.channel {
+                Some(x) => x.clone(),
+                None => {
+                    return Err(Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("version {} does not belong to any channel", ver.nice_name),
+                    ));
+                }
+            },
+            None => return Err(Error::new(std::io::ErrorKind::NotFound, "no such version")),
+        };
+        // channel becomes part of file names
+        if Path::new(&channel).file_name() != Some(channel.as_ref()) {
+            return Err(Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("channel name {:?} cannot be used in file names", channel),
+            ));
+        }
+
+        #[cfg(unix)]
+        self.make_version_current_unix(i, &format!("{}{}", CHANNEL_CURRENT_PREFIX, channel))?;
+        self.helper_make_channel_launchers(i, &channel)
+    }
+
+    ///
+    /// helper func
+    ///
+    /// (re)make launchers of given channel, whose current is version i
+    ///
+    fn helper_make_channel_launchers(&self, i: usize, channel: &str) -> Result<(), Error> {
+        let has_viewer = self.versions[i].has_viewer;
+        let launcher_name = format!("{}{}", CHANNEL_LAUNCHER_PREFIX, channel);
+        let viewer_launcher_name = format!("{}{}", CHANNEL_VIEWER_LAUNCHER_PREFIX, channel);
+        #[cfg(unix)]
+        {
+            let current_name = format!("{}{}", CHANNEL_CURRENT_PREFIX, channel);
+            Self::helper_make_script_link(
+                &current_name,
+                &self.base_path.join(launcher_name),
+                "",
+                false,
+            )?;
+            if has_viewer {
+                Self::helper_make_script_link(
+                    &current_name,
+                    &self.base_path.join(viewer_launcher_name),
+                    "viewer",
+                    false,
+                )?;
+            }
+        }
+        #[cfg(windows)]
+        {
+            // there are no links, launchers point to the version directly
+            let ver = &self.versions[i];
+            let path_to_ver = if let Ok(path) = ver.path.strip_prefix(&self.base_path) {
+                path
+            } else {
+                &ver.path
+            };
+            // extension is given explicitly, as channel may have dots in it
+            Self::helper_make_script_link(
+                &path_to_ver.to_string_lossy(),
+                &self.base_path.join(format!("{}.cmd", launcher_name)),
+                "",
+                false,
+            )?;
+            if has_viewer {
+                Self::helper_make_script_link(
+                    &path_to_ver.to_string_lossy(),
+                    &self.base_path.join(format!("{}.cmd", viewer_launcher_name)),
+                    "viewer",
+                    false,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// check if given branch has a commit that is not installed yet
+    ///
+    /// only head commit information is downloaded, nothing is installed
+    ///
+    pub fn check_for_update(&self, branch: &str) -> Result<UpdateInfo, Error> {
+        let (commit, date) = Self::helper_get_head_commit(
+            &std::env::temp_dir(),
+            &self.download_source,
+            &SourceRef::Branch(branch.to_owned()),
+            &self.cancel,
+        )?;
+        let installed_version_index = self.versions.iter().position(|x| x.commit == commit);
+
+        Ok(UpdateInfo {
+            commit,
+            date,
+            installed_version_index,
+        })
+    }
+
+    ///
+    /// check health of given installed version
+    ///
+    /// unlike the scan done by from_dir - this actually runs version's venv python,
+    /// checks that lifeblood can be imported and that pip sees no broken requirements,
+    /// and also checks that launcher scripts that run this version
+    /// (main ones if it's current, and ones of channels it's current of) point to it
+    ///
+    /// failed checks do not produce an error, they are listed in returned report
+    ///
+    pub fn verify_version(&self, version_id: usize) -> Result<VerificationReport, Error> {
+        let ver = match self.versions.get(version_id) {
+            Some(x) => x,
+            None => return Err(Error::new(std::io::ErrorKind::NotFound, "no such version")),
+        };
+        let mut report = VerificationReport::default();
+        let python_bin = Self::helper_get_venv_relative_python_bin_path(&ver.path);
+
+        let (python_ok, details) =
+            Self::helper_run_python_check(&ver.path, &python_bin, &["--version"]);
+        report.add_check("python starts", python_ok, details);
+
+        let mut python_checks = vec![("import lifeblood", vec!["-c", "import lifeblood"])];
+        if ver.has_viewer {
+            python_checks.push((
+                "import lifeblood_viewer",
+                vec!["-c", "import lifeblood_viewer"],
+            ));
+        }
+        python_checks.push(("pip check", vec!["-m", "pip", "check"]));
+        for (name, args) in python_checks {
+            if !python_ok {
+                report.add_check(name, false, "venv python does not start".to_owned());
+                continue;
+            }
+            let (passed, details) = Self::helper_run_python_check(&ver.path, &python_bin, &args);
+            report.add_check(name, passed, details);
+        }
+
+        let mut launchers = Vec::new();
+        if version_id == self.current_version {
+            launchers.push(("lifeblood".to_owned(), true));
+            launchers.push(("lifeblood_viewer".to_owned(), ver.has_viewer));
+        }
+        for channel in self.helper_version_channels(version_id) {
+            launchers.push((format!("{}{}", CHANNEL_LAUNCHER_PREFIX, channel), true));
+            launchers.push((
+                format!("{}{}", CHANNEL_VIEWER_LAUNCHER_PREFIX, channel),
+                ver.has_viewer,
+            ));
+        }
+        for (launcher_name, required) in launchers {
+            let launcher_path = self.base_path.join(&launcher_name);
+            // extension is given explicitly, as channel may have dots in it
+            #[cfg(windows)]
+            let launcher_path = self.base_path.join(format!("{}.cmd", launcher_name));
+            if !required && !launcher_path.exists() {
+                continue;
+            }
+            let (passed, details) = match Self::helper_get_script_link_target(&launcher_path) {
+                Ok(target) => {
+                    let target_path = self.base_path.join(&target);
+                    // main launchers prefer this host's own current
+                    #[cfg(unix)]
+                    let target_path = match host_current_link_name() {
+                        Some(x) if target == "current" && self.base_path.join(&x).exists() => {
+                            self.base_path.join(x)
+                        }
+                        _ => target_path,
+                    };
+                    let is_this_version = target_path
+                        .canonicalize()
+                        .is_ok_and(|x| ver.path.canonicalize().is_ok_and(|y| x == y));
+                    if !is_this_version {
+                        (false, format!("points to {:?}, not to this version", target_path))
+                    } else if Self::helper_get_venv_relative_python_bin_path(&target_path).exists()
+                        && target_path.join("entry.py").exists()
+                    {
+                        (true, format!("points to {:?}", target_path))
+                    } else {
+                        (false, format!("target {:?} is not a valid version", target_path))
+                    }
+                }
+                Err(e) => (false, e.to_string()),
+            };
+            report.add_check(&format!("{} launcher", launcher_name), passed, details);
+        }
+
+        Ok(report)
+    }
+
+    ///
+    /// try to fix given installed version in place, without downloading it again
+    ///
+    /// if venv python does not start - venv is recreated with the python
+    /// the version was installed with, or with system python if that one is gone,
+    /// in both cases it has to be supported by the version. broken venv is kept
+    /// until the new one is made, and put back if that fails.
+    /// then pip is rerun against stored requirements, pth file is rewritten
+    /// and launcher scripts are regenerated
+    ///
+    /// version that is in use cannot be repaired
+    ///
+    /// this is a long operation, as it involves installing a bunch of pip packages
+    ///
+    pub fn repair_version(&mut self, version_id: usize) -> Result<(), InstallError> {
+        let result = self.helper_repair_version(version_id);
+        let result = self.check_cancelled(result);
+        self.report_finished(result.as_ref().map(|_| version_id));
+        result
+    }
+
+    fn helper_repair_version(&mut self, version_id: usize) -> Result<(), InstallError> {
+        let _lock = self.lock_base_dir().phase(InstallError::Locked)?;
+        let ver = match self.versions.get(version_id) {
+            Some(x) => x,
+            None => {
+                return Err(InstallError::Other(Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "no such version",
+                )));
+            }
+        };
+        let dest_dir = dunce::simplified(&ver.path).to_path_buf();
+        let has_viewer = ver.has_viewer;
+
+        let mut requirements = vec![dest_dir.join("requirements.txt")];
+        if has_viewer {
+            requirements.push(dest_dir.join("requirements_viewer.txt"));
+        }
+        if let Some(missing) = requirements.iter().find(|x| !x.exists()) {
+            return Err(InstallError::Requirements(Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("cannot repair, {:?} is missing", missing),
+            )));
+        }
+
+        if is_version_in_use(&dest_dir) {
+            return Err(InstallError::Other(Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!(
+                    "cannot repair version {}, there are processes running from it",
+                    ver.nice_name
+                ),
+            )));
+        }
+
+        // python is checked before anything is touched, as venv may need to be remade with it
+        let python_requires = match &ver.python_requires {
+            Some(x) => x.parse().map_err(InstallError::Requirements)?,
+            None => VersionSpecifiers::default(),
+        };
+        let python_to_use = match &ver.python {
+            Some(python) if Self::helper_get_python_version(python).is_some() => {
+                Some(python.to_owned())
+            }
+            _ if self.auto_select_python => None,
+            _ => get_python_command(),
+        };
+        let python_to_use = self.helper_resolve_python(python_to_use.as_deref(), &python_requires)?;
+
+        // broken venv is only moved aside, to be put back if it cannot be remade
+        let python_bin = Self::helper_get_venv_relative_python_bin_path(&dest_dir);
+        let (python_ok, _) = Self::helper_run_python_check(&dest_dir, &python_bin, &["--version"]);
+        let venv_path = dest_dir.join("venv");
+        let mut broken_venv_path = None;
+        if !python_ok && venv_path.exists() {
+            println!("venv python does not start, recreating venv");
+            let aside_path = dest_dir.join(format!("__broken_venv_{}", helper_random_name()));
+            if let Err(e) = fs::rename(&venv_path, &aside_path) {
+                return Err(InstallError::Venv(Error::new(
+                    e.kind(),
+                    format!("failed to move broken venv aside: {}", e),
+                )));
+            }
+            broken_venv_path = Some(aside_path);
+        }
+
+        // if exact versions are known - venv is repaired to them
+        let lock_path = dest_dir.join(LOCK_FILE_NAME);
+        let constraints_path = if lock_path.exists() {
+            Some(lock_path.as_path())
+        } else {
+            None
+        };
+        let result = requirements.iter().try_for_each(|requirements_path| {
+            Self::helper_install_venv(
+                &dest_dir,
+                requirements_path,
+                constraints_path,
+                python_to_use.as_deref(),
+                &self.installer_settings,
+                &self.progress,
+                &self.cancel,
+            )
+        });
+        if let Some(aside_path) = broken_venv_path {
+            if result.is_err() {
+                if venv_path.exists() {
+                    if let Err(e) = fs::remove_dir_all(&venv_path) {
+                        eprintln!("failed to remove partially made venv: {}", e);
+                    }
+                }
+                if let Err(e) = fs::rename(&aside_path, &venv_path) {
+                    eprintln!("failed to put old venv back from {:?}: {}", aside_path, e);
+                }
+            } else if let Err(e) = fs::remove_dir_all(&aside_path) {
+                eprintln!("failed to remove old venv {:?}, remove it manually: {}", aside_path, e);
+            }
+        }
+        result?;
+
+        // embedded windows python finds lifeblood through it's own _pth file
+        if !venv_path.join(VENV_BIN).join("sitecustomize.py").exists() {
+            Self::helper_write_pth_file(&dest_dir).phase(InstallError::Venv)?;
+        }
+        Self::helper_record_lock_file(&dest_dir);
+
+        // (re)make shortcuts
+        #[cfg(unix)]
+        {
+            Self::helper_make_script_link("current", &self.base_path.join("lifeblood"), "", true)
+                .phase(InstallError::Link)?;
+            if has_viewer || self.base_path.join("lifeblood_viewer").exists() {
+                Self::helper_make_script_link(
+                    "current",
+                    &self.base_path.join("lifeblood_viewer"),
+                    "viewer",
+                    true,
+                )
+                .phase(InstallError::Link)?;
+            }
+        }
+        #[cfg(windows)]
+        if version_id == self.current_version {
+            self.make_version_current_win(version_id, has_viewer)
+                .phase(InstallError::Link)?;
+        }
+        for channel in self.helper_version_channels(version_id) {
+            self.helper_make_channel_launchers(version_id, &channel)
+                .phase(InstallError::Link)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// add viewer to an already installed version that does not have it
     ///
-    pub fn current_version(&self) -> Option<&InstalledVersion> {
-        if self.current_version == usize::MAX {
-            None
-        } else {
-            if let Some(ver) = self.versions.get(self.current_version) {
-                Some(ver)
+    /// source archive of version's commit is taken from download cache or downloaded,
+    /// but only viewer sources are taken from it, and only viewer requirements
+    /// are installed into existing venv. the rest of the version is not touched
+    ///
+    pub fn add_viewer(&mut self, version_id: usize) -> Result<(), InstallError> {
+        let result = self.download_and_add_viewer(version_id);
+        let result = self.check_cancelled(result);
+        self.report_finished(result.as_ref().map(|_| version_id));
+        result
+    }
+
+    fn download_and_add_viewer(&mut self, version_id: usize) -> Result<(), InstallError> {
+        let _lock = self.lock_base_dir().phase(InstallError::Locked)?;
+        let ver = match self.versions.get(version_id) {
+            Some(x) => x,
+            None => {
+                return Err(InstallError::Other(Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "no such version",
+                )));
+            }
+        };
+        if ver.has_viewer {
+            println!("version {} already has viewer", ver.nice_name);
+            return Ok(());
+        }
+        if !is_valid_commit_hash(&ver.commit) {
+            return Err(InstallError::Download(Error::new(
+                std::io::ErrorKind::InvalidData,
+                "commit of the version is unknown, cannot get viewer sources for it",
+            )));
+        }
+        let source_ref = SourceRef::Commit(ver.commit.clone());
+
+        let mut downloaded_zip = None;
+        let zip_file = match self.download_cache().and_then(|cache| cache.find(&source_ref)) {
+            Some(x) => x,
+            None => {
+                println!("downloading {} to get viewer", source_ref);
+                self.cancel.check()?;
+                self.progress.phase(InstallPhase::Download);
+                let path = Self::helper_download(
+                    &std::env::temp_dir(),
+                    &self.download_source,
+                    &source_ref,
+                    &self.progress,
+                    &self.cancel,
+                )
+                .phase(InstallError::Download)?;
+                // keep archive for the future, failing that is not a reason to fail
+                if let Some(cache) = self.download_cache() {
+                    if let Err(e) = cache.store(&path, source_ref.name(), Some(&source_ref)) {
+                        eprintln!("failed to put archive into download cache: {}", e);
+                    }
+                }
+                downloaded_zip = Some(path.clone());
+                path
+            }
+        };
+
+        let unzip_location = std::env::temp_dir().join(helper_random_name());
+        self.progress.phase(InstallPhase::Unpack);
+        let result = fs::create_dir(&unzip_location)
+            .and_then(|_| {
+                Self::helper_unpack(&zip_file, &unzip_location, &self.progress, &self.cancel)
+            })
+            .phase(InstallError::Unpack)
+            .and_then(|(commit, _)| {
+                self.cancel.check()?;
+                if commit.trim() != source_ref.name() {
+                    return Err(InstallError::Unpack(Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("archive contains unexpected commit {}", commit.trim()),
+                    )));
+                }
+                self.helper_add_viewer(version_id, &unzip_location)
+            });
+
+        // temporary stuff is not needed any more
+        self.progress.phase(InstallPhase::Cleanup);
+        let mut temp_paths = vec![unzip_location];
+        temp_paths.extend(downloaded_zip);
+        for path in temp_paths {
+            println!("removing: {:?}", path);
+            let cleanup_result = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else if path.exists() {
+                fs::remove_file(&path)
             } else {
-                None
+                Ok(())
+            };
+            if let Err(e) = cleanup_result {
+                eprintln!(
+                    "failed to cleanup temporary files, please remove them manually: {}",
+                    e
+                );
             }
         }
+
+        result
     }
 
     ///
-    /// index of current version
+    /// helper func
     ///
-    pub fn current_version_index(&self) -> usize {
-        self.current_version
+    /// add viewer to given version from given unpacked source archive
+    ///
+    fn helper_add_viewer(
+        &mut self,
+        version_id: usize,
+        unzip_location: &Path,
+    ) -> Result<(), InstallError> {
+        let ver = match self.versions.get_mut(version_id) {
+            Some(x) => x,
+            None => {
+                return Err(InstallError::Other(Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "no such version",
+                )));
+            }
+        };
+        let dest_dir = dunce::simplified(&ver.path).to_path_buf();
+
+        // we expect a single folder inside
+        let inner_dir = match unzip_location.read_dir().phase(InstallError::Unpack)?.next() {
+            Some(Ok(dir)) => dir.path(),
+            _ => {
+                return Err(InstallError::Unpack(Error::new(
+                    std::io::ErrorKind::Other,
+                    "no dir suitable found in unzip location",
+                )));
+            }
+        };
+
+        let requirements_path_viewer = dest_dir.join("requirements_viewer.txt");
+        let metadata = PackageMetadata::from_package_dir(&inner_dir.join("pkg_lifeblood_viewer"))
+            .phase(InstallError::Requirements)?;
+        Self::helper_write_strings_to_file(metadata.requirements, &requirements_path_viewer)
+            .phase(InstallError::Requirements)?;
+
+        self.progress.phase(InstallPhase::CopyModules);
+        if let Err(e) =
+            Self::helper_copy_dir(&inner_dir.join("src").join("lifeblood_viewer"), &dest_dir)
+        {
+            return Err(InstallError::Other(Error::new(
+                e.kind(),
+                format!("copying viewer modules failed: {}", e),
+            )));
+        }
+        // viewer's requirements must not change versions of what's already there
+        let lock_path = dest_dir.join(LOCK_FILE_NAME);
+        let constraints_path = if lock_path.exists() {
+            Some(lock_path.as_path())
+        } else {
+            None
+        };
+        if let Err(e) = Self::helper_install_venv(
+            &dest_dir,
+            &requirements_path_viewer,
+            constraints_path,
+            ver.python.as_deref(),
+            &self.installer_settings,
+            &self.progress,
+            &self.cancel,
+        ) {
+            // without viewer module version is not considered to have viewer
+            if let Err(e) = fs::remove_dir_all(dest_dir.join("lifeblood_viewer")) {
+                eprintln!("failed to remove partially added viewer: {}", e);
+            }
+            return Err(e);
+        }
+
+        Self::helper_record_lock_file(&dest_dir);
+
+        ver.has_viewer = true;
+        ver.metadata()
+            .write_to_file(&dest_dir.join("meta.info"))
+            .phase(InstallError::Metadata)?;
+
+        #[cfg(unix)]
+        Self::helper_make_script_link(
+            "current",
+            &self.base_path.join("lifeblood_viewer"),
+            "viewer",
+            true,
+        )
+        .phase(InstallError::Link)?;
+        #[cfg(windows)]
+        if version_id == self.current_version {
+            self.make_version_current_win(version_id, true)
+                .phase(InstallError::Link)?;
+        }
+        // channels using this version get their viewer launchers as well
+        for channel in self.helper_version_channels(version_id) {
+            self.helper_make_channel_launchers(version_id, &channel)
+                .phase(InstallError::Link)?;
+        }
+
+        Ok(())
     }
 
     ///
-    /// iterator over versions
+    /// write given version into a single archive, from which it can be installed
+    /// with import_bundle on machines without network access
     ///
-    pub fn iter_versions(
+    /// archive has version's sources, metadata, requirements and lock files,
+    /// and wheels of every package of version's venv, built for version's python,
+    /// so it can be imported where the same python version on the same platform is available
+    ///
+    /// wheels are taken from wherever installer settings point to, so network is needed here
+    ///
+    pub fn export_version(&self, version_id: usize, out_path: &Path) -> Result<(), InstallError> {
+        let result = self.helper_export_version(version_id, out_path);
+        let result = self.check_cancelled(result);
+        self.report_finished(result.as_ref().map(|_| version_id));
+        result
+    }
+
+    fn helper_export_version(
         &self,
-    ) -> impl DoubleEndedIterator<Item = &InstalledVersion> + ExactSizeIterator + '_ {
-        self.versions.iter()
+        version_id: usize,
+        out_path: &Path,
+    ) -> Result<(), InstallError> {
+        let ver = match self.versions.get(version_id) {
+            Some(x) => x,
+            None => {
+                return Err(InstallError::Other(Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "no such version",
+                )));
+            }
+        };
+        let dest_dir = dunce::simplified(&ver.path).to_path_buf();
+        println!("exporting version {} to {:?}", ver.nice_name, out_path);
+
+        let temp_dir = std::env::temp_dir().join(helper_random_name());
+        fs::create_dir(&temp_dir).phase(InstallError::Other)?;
+        let result = self.helper_write_bundle(&dest_dir, &temp_dir, out_path);
+
+        self.progress.phase(InstallPhase::Cleanup);
+        println!("removing: {:?}", temp_dir);
+        if let Err(e) = fs::remove_dir_all(&temp_dir) {
+            eprintln!(
+                "failed to cleanup temporary files, please remove them manually: {}",
+                e
+            );
+        }
+
+        result
     }
 
     ///
-    /// number of versions found
+    /// helper func
     ///
-    pub fn version_count(&self) -> usize {
-        self.versions.len()
+    /// collect wheels for version in dest_dir into temp_dir and write bundle with them
+    ///
+    fn helper_write_bundle(
+        &self,
+        dest_dir: &Path,
+        temp_dir: &Path,
+        out_path: &Path,
+    ) -> Result<(), InstallError> {
+        // exact versions are what makes the bundle reproducible
+        let mut lock_path = dest_dir.join(LOCK_FILE_NAME);
+        if !lock_path.exists() {
+            lock_path = temp_dir.join(LOCK_FILE_NAME);
+            Self::helper_freeze_venv(dest_dir)
+                .and_then(|lock| fs::write(&lock_path, lock))
+                .phase(InstallError::Requirements)?;
+        }
+
+        self.cancel.check()?;
+        self.progress.phase(InstallPhase::CollectWheels);
+        let wheels_dir = temp_dir.join(version_bundle::WHEELS_DIR_NAME);
+        fs::create_dir(&wheels_dir).phase(InstallError::Other)?;
+        version_bundle::collect_wheels(
+            &Self::helper_get_venv_relative_python_bin_path(dest_dir),
+            &lock_path,
+            &wheels_dir,
+            &self.installer_settings.pip_index_args(),
+            &self.progress,
+            &self.cancel,
+        )
+        .phase(InstallError::Pip)?;
+
+        self.cancel.check()?;
+        self.progress.phase(InstallPhase::Pack);
+        version_bundle::write_bundle(out_path, dest_dir, &lock_path, &wheels_dir)
+            .phase(InstallError::Other)
     }
 
     ///
-    /// set given version by index as current
+    /// install a version from a bundle made by export_version, without network access
     ///
-    /// concept of "current" is implemented differently on different platforms
-    /// in any way this will cause modifications to the filesystem
+    /// venv is created with given python, or, if auto python selection is on,
+    /// with one of the same minor version as the bundle was made with,
+    /// and packages are installed only from bundled wheels, exactly as they are locked there
     ///
-    /// on unix - "current" link will be changed
-    /// on windows - lifeblood.cmd lifeblood_viewer.cmd will be changed directly
+    /// returns index of the new version, or of an already installed one with the same commit
     ///
-    pub fn make_version_current(&mut self, i: usize) -> Result<(), Error> {
-        #[cfg(unix)]
-        return self.make_version_current_unix(i);
-        #[cfg(windows)]
-        return self.make_version_current_win(i, false);
+    pub fn import_bundle(
+        &mut self,
+        bundle_path: &Path,
+        python_to_use: Option<&Path>,
+    ) -> Result<usize, InstallError> {
+        let result = self
+            .lock_base_dir()
+            .phase(InstallError::Locked)
+            .and_then(|_lock| self.helper_import_bundle(bundle_path, python_to_use));
+        let result = self.check_cancelled(result);
+        self.report_finished(result.as_ref().copied());
+        result
+    }
+
+    fn helper_import_bundle(
+        &mut self,
+        bundle_path: &Path,
+        python_to_use: Option<&Path>,
+    ) -> Result<usize, InstallError> {
+        println!("importing bundle {:?}", bundle_path);
+        let install_start = Instant::now();
+
+        let unzip_location = std::env::temp_dir().join(helper_random_name());
+        self.progress.phase(InstallPhase::Unpack);
+        let result = fs::create_dir(&unzip_location)
+            .and_then(|_| {
+                Self::helper_unpack(bundle_path, &unzip_location, &self.progress, &self.cancel)
+            })
+            .phase(InstallError::Unpack)
+            .and_then(|_| {
+                self.cancel.check()?;
+                self.helper_install_bundle(&unzip_location, python_to_use, install_start)
+            });
+
+        // temporary stuff is not needed any more
+        self.progress.phase(InstallPhase::Cleanup);
+        println!("removing: {:?}", unzip_location);
+        if unzip_location.exists() {
+            if let Err(e) = fs::remove_dir_all(&unzip_location) {
+                eprintln!(
+                    "failed to cleanup temporary files, please remove them manually: {}",
+                    e
+                );
+            }
+        }
+
+        result
+    }
+
+    ///
+    /// helper func
+    ///
+    /// install version from unpacked bundle
+    ///
+    fn helper_install_bundle(
+        &mut self,
+        unzip_location: &Path,
+        python_to_use: Option<&Path>,
+        install_start: Instant,
+    ) -> Result<usize, InstallError> {
+        let bundle_version_dir = unzip_location.join(version_bundle::VERSION_DIR_NAME);
+        let metadata_path = bundle_version_dir.join("meta.info");
+        let metadata = match VersionMetadata::read_from_file(&metadata_path) {
+            Ok(x) => x,
+            Err(e) => {
+                return Err(InstallError::Unpack(Error::new(
+                    e.kind(),
+                    format!("failed to read bundle metadata, is it a version bundle? {}", e),
+                )));
+            }
+        };
+        if let Some(i) = self.versions.iter().position(|x| x.commit == metadata.commit) {
+            println!("commit {} already installed!", metadata.commit);
+            return Ok(i);
+        }
+        let nice_name = metadata.nice_name.clone();
+        if nice_name.is_empty() || Path::new(&nice_name).file_name() != Some(nice_name.as_ref()) {
+            return Err(InstallError::Unpack(Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("bundle has invalid version name {:?}", nice_name),
+            )));
+        }
+        let dest_dir = self.base_path.join(&nice_name);
+        if dest_dir.exists() {
+            return Err(InstallError::Other(Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{:?} already exists", dest_dir),
+            )));
+        }
+
+        // wheels are only good for the python they were built with
+        let python_requires = match metadata
+            .python_version
+            .as_deref()
+            .and_then(PythonVersion::interpreter_release)
+        {
+            Some(x) => {
+                let minor: Vec<String> =
+                    x.release().iter().take(2).map(|x| x.to_string()).collect();
+                format!("=={}.*", minor.join(".")).parse().unwrap_or_default()
+            }
+            None => VersionSpecifiers::default(),
+        };
+        let has_viewer = bundle_version_dir.join("lifeblood_viewer").exists();
+
+        let result = self.helper_install_bundle_files(
+            unzip_location,
+            &dest_dir,
+            has_viewer,
+            python_to_use,
+            &python_requires,
+        );
+        let python_to_use = match result {
+            Ok(x) => x,
+            Err(e) => {
+                if dest_dir.exists() {
+                    if let Err(e) = fs::remove_dir_all(&dest_dir) {
+                        eprintln!("failed to remove partially imported version: {}", e);
+                    }
+                }
+                return Err(e);
+            }
+        };
+
+        let metadata = VersionMetadata {
+            python: Self::helper_get_venv_base_python(&dest_dir).or(python_to_use),
+            python_version: Self::helper_get_python_version(
+                &Self::helper_get_venv_relative_python_bin_path(&dest_dir),
+            ),
+            has_viewer: Some(has_viewer),
+            manager_build: Some(BUILD_INFO.to_owned()),
+            install_duration_secs: Some(install_start.elapsed().as_secs_f64()),
+            ..metadata
+        };
+        self.helper_register_version(dest_dir, metadata)
+    }
+
+    ///
+    /// helper func
+    ///
+    /// copy files from unpacked bundle to dest_dir and make venv there from bundled wheels
+    ///
+    /// returns python that was used
+    ///
+    fn helper_install_bundle_files(
+        &self,
+        unzip_location: &Path,
+        dest_dir: &Path,
+        has_viewer: bool,
+        python_to_use: Option<&Path>,
+        python_requires: &VersionSpecifiers,
+    ) -> Result<Option<PathBuf>, InstallError> {
+        let python_to_use = self.helper_resolve_python(python_to_use, python_requires)?;
+
+        self.progress.phase(InstallPhase::CopyModules);
+        let mut copy_options = CopyOptions::new();
+        copy_options.content_only = true;
+        fs::create_dir(dest_dir)
+            .and_then(|_| {
+                fs_extra::dir::copy(
+                    unzip_location.join(version_bundle::VERSION_DIR_NAME),
+                    dest_dir,
+                    &copy_options,
+                )
+                .map_err(|e| Error::new(std::io::ErrorKind::Other, e.to_string()))
+            })
+            .map_err(|e| Error::new(e.kind(), format!("copying bundled files failed: {}", e)))
+            .phase(InstallError::Other)?;
+
+        // nothing but bundled wheels is used
+        let installer_settings = InstallerSettings {
+            wheelhouse: Some(unzip_location.join(version_bundle::WHEELS_DIR_NAME)),
+            ..self.installer_settings.clone()
+        };
+        let lock_path = dest_dir.join(LOCK_FILE_NAME);
+        let constraints_path = if lock_path.exists() {
+            Some(lock_path.as_path())
+        } else {
+            None
+        };
+        let mut requirements = vec![dest_dir.join("requirements.txt")];
+        if has_viewer {
+            requirements.push(dest_dir.join("requirements_viewer.txt"));
+        }
+        for requirements_path in requirements.iter() {
+            Self::helper_install_venv(
+                dest_dir,
+                requirements_path,
+                constraints_path,
+                python_to_use.as_deref(),
+                &installer_settings,
+                &self.progress,
+                &self.cancel,
+            )?;
+        }
+        Self::helper_record_lock_file(dest_dir);
+
+        Ok(python_to_use)
     }
 
+    ///
+    /// point link with given name to given version,
+    /// current version changes if that link is the one this host uses
+    ///
     #[cfg(unix)]
-    fn make_version_current_unix(&mut self, i: usize) -> Result<(), Error> {
+    fn make_version_current_unix(&mut self, i: usize, link_name: &str) -> Result<(), Error> {
         match self.versions.get(i) {
             Some(ver) => {
                 // try to get a relpath
@@ -385,21 +2388,34 @@ impl InstallationsData {
                     &ver.path
                 };
 
-                let path_to_current = self.base_path.join("current");
-                if path_to_current.is_symlink() || path_to_current.exists() {
-                    if let Err(e) = fs::remove_file(&path_to_current) {
-                        return Err(Error::new(
-                            e.kind(),
-                            format!("failed to remove 'current' link: {}", e),
-                        ));
-                    }
+                // new link is made aside and then renamed over the old one,
+                // so there is no moment without current, and on failure the old one stays
+                let path_to_current = self.base_path.join(link_name);
+                let temp_link = self
+                    .base_path
+                    .join(format!(".current.{}", helper_random_name()));
+                if let Err(e) = std::os::unix::fs::symlink(path_to_ver, &temp_link) {
+                    return Err(Error::new(
+                        e.kind(),
+                        format!("failed to create temporary '{}' link: {}", link_name, e),
+                    ));
                 }
-                if let Err(e) = std::os::unix::fs::symlink(path_to_ver, &path_to_current) {
-                    // TODO: restore prev current
-                    return Err(e);
+                if let Err(e) = fs::rename(&temp_link, &path_to_current) {
+                    if let Err(remove_err) = fs::remove_file(&temp_link) {
+                        eprintln!(
+                            "failed to remove temporary link {:?}: {}",
+                            temp_link, remove_err
+                        );
+                    }
+                    return Err(Error::new(
+                        e.kind(),
+                        format!("failed to replace '{}' link: {}", link_name, e),
+                    ));
                 }
 
-                self.current_version = i;
+                if link_name == self.current_link_name() {
+                    self.current_version = i;
+                }
                 Ok(())
             }
             None => Err(Error::new(std::io::ErrorKind::NotFound, "no such version")),
@@ -416,19 +2432,38 @@ impl InstallationsData {
                     &ver.path
                 };
 
+                let path_to_cmd = self.base_path.join("lifeblood.cmd");
+                let path_to_viewer_cmd = self.base_path.join("lifeblood_viewer.cmd");
+                // each script is replaced atomically, but there are two of them,
+                // so remember the first one to restore it if the second one fails
+                let prev_cmd_contents = fs::read(&path_to_cmd).ok();
+
                 Self::helper_make_script_link(
                     &path_to_ver.to_string_lossy(),
-                    &self.base_path.join("lifeblood.cmd"),
+                    &path_to_cmd,
                     "",
+                    false,
                 )?;
 
                 // now here - if lifeblood_viewer.cmd already exists - we relink it anyway, but if not - we use do_viewer
-                if do_viewer || self.base_path.join("lifeblood_viewer.cmd").exists() {
-                    Self::helper_make_script_link(
+                if do_viewer || path_to_viewer_cmd.exists() {
+                    if let Err(e) = Self::helper_make_script_link(
                         &path_to_ver.to_string_lossy(),
-                        &self.base_path.join("lifeblood_viewer.cmd"),
+                        &path_to_viewer_cmd,
                         "viewer",
-                    )?;
+                        false,
+                    ) {
+                        let restore_result = match prev_cmd_contents {
+                            Some(contents) => {
+                                Self::helper_write_file_atomically(&path_to_cmd, &contents)
+                            }
+                            None => fs::remove_file(&path_to_cmd),
+                        };
+                        if let Err(restore_err) = restore_result {
+                            eprintln!("failed to restore previous lifeblood.cmd: {}", restore_err);
+                        }
+                        return Err(e);
+                    }
                 }
 
                 self.current_version = i;
@@ -439,89 +2474,267 @@ impl InstallationsData {
     }
 
     ///
-    /// download freshest commit from given branch, and makes an "installation" of it
+    /// download freshest commit from given ref, and makes an "installation" of it
+    ///
+    /// given branch head, tag or exact commit will be downloaded and "installed"  into base_path.
+    /// given ref is saved into version's metadata
+    /// if do_install_viewer is true - viewer will also be installed
+    ///
+    /// This operation will modify current base_dir, but will not switch current if it already exists
+    /// during the process a bunch of files will be downloaded and unzipped into system's temporary location
+    /// this will all be cleared on both success and failure
+    ///
+    /// if download cache is enabled - downloaded archive is kept there,
+    /// and tags and commits found in cache are not downloaded again.
+    /// branches are always downloaded, as there is no telling where their head is now
+    ///
+    /// if retention policy is enabled - old versions are pruned after successful installation,
+    /// the version just installed is always kept
+    ///
+    /// progress is reported to the callback given to set_progress_callback
+    /// and operation can be cancelled with the token given to set_cancellation_token,
+    /// then InstallError::Cancelled is returned and base_dir is left as it was before
+    ///
+    /// this is a long operation, as it involves downloading and installing a bunch of pip packages
+    ///
+    pub fn download_new_version(
+        &mut self,
+        source_ref: &SourceRef,
+        do_install_viewer: bool,
+        python_to_use: Option<&Path>,
+    ) -> Result<usize, InstallError> {
+        let result = self.download_install_and_prune(source_ref, do_install_viewer, python_to_use);
+        let result = self.check_cancelled(result);
+        self.report_finished(result.as_ref().copied());
+        result
+    }
+
+    fn download_install_and_prune(
+        &mut self,
+        source_ref: &SourceRef,
+        do_install_viewer: bool,
+        python_to_use: Option<&Path>,
+    ) -> Result<usize, InstallError> {
+        let _lock = self.lock_base_dir().phase(InstallError::Locked)?;
+        let new_index = self.download_and_install(source_ref, do_install_viewer, python_to_use)?;
+        if !self.retention_policy.is_enabled() {
+            return Ok(new_index);
+        }
+
+        let new_path = self.versions[new_index].path.clone();
+        let to_prune = self.helper_versions_to_prune(new_index);
+        if let Err(e) = self.helper_remove_versions(to_prune) {
+            // new version is installed, so this is not a reason to fail
+            eprintln!("failed to prune old versions: {}", e);
+        }
+        match self.versions.iter().position(|x| x.path == new_path) {
+            Some(i) => Ok(i),
+            None => Err(InstallError::Other(Error::new(
+                std::io::ErrorKind::NotFound,
+                "new version disappeared while pruning",
+            ))),
+        }
+    }
+
+    fn download_and_install(
+        &mut self,
+        source_ref: &SourceRef,
+        do_install_viewer: bool,
+        python_to_use: Option<&Path>,
+    ) -> Result<usize, InstallError> {
+        let temp_location = std::env::temp_dir();
+
+        // exact commit may already be installed, then no need to download anything
+        if let SourceRef::Commit(commit) = source_ref {
+            SourceRef::check_commit_prefix(commit).map_err(InstallError::Other)?;
+            let installed: Vec<usize> = (0..self.versions.len())
+                .filter(|&i| self.versions[i].commit.starts_with(commit.as_str()))
+                .collect();
+            if installed.len() > 1 {
+                return Err(InstallError::Other(Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "commit {} is ambiguous, it matches {} installed versions",
+                        commit,
+                        installed.len()
+                    ),
+                )));
+            }
+            if let Some(&i) = installed.first() {
+                println!("commit {} already downloaded!", commit);
+                if do_install_viewer {
+                    self.download_and_add_viewer(i)?;
+                }
+                return Ok(i);
+            }
+        }
+
+        // maybe it's already in download cache
+        // branch is looked up by its head commit, as it may have moved since it was cached,
+        // that's only worth a try, if it fails - branch is just downloaded
+        let cache = self.download_cache();
+        let cache_ref = match source_ref {
+            SourceRef::Branch(_) if !self.download_source.has_commit_info() => None,
+            SourceRef::Branch(_) if cache.as_ref().is_some_and(|x| !x.is_empty()) => {
+                match Self::helper_get_head_commit(
+                    &temp_location,
+                    &self.download_source,
+                    source_ref,
+                    &self.cancel,
+                ) {
+                    Ok((commit, _)) => Some(SourceRef::Commit(commit)),
+                    Err(e) => {
+                        eprintln!("failed to get head commit of {}: {}", source_ref, e);
+                        None
+                    }
+                }
+            }
+            _ => Some(source_ref.clone()),
+        };
+        if let Some(cached_zip) = cache
+            .zip(cache_ref)
+            .and_then(|(cache, cache_ref)| cache.find(&cache_ref))
+        {
+            return self.install_from_archive(
+                &cached_zip,
+                Some(source_ref),
+                do_install_viewer,
+                python_to_use,
+                Vec::new(),
+            );
+        }
+
+        //
+        println!(
+            "downloading {}, viewer too: {}",
+            source_ref, do_install_viewer
+        );
+
+        //
+        // download phase
+        self.progress.phase(InstallPhase::Download);
+        let downloaded_zip = self
+            .cancel
+            .check()
+            .and_then(|_| {
+                Self::helper_download(
+                    &temp_location,
+                    &self.download_source,
+                    source_ref,
+                    &self.progress,
+                    &self.cancel,
+                )
+            })
+            .phase(InstallError::Download)?;
+        // add cleanup for downloaded stuff
+        let cleanup_downloaded_zip = downloaded_zip.clone();
+        let cleanups: Vec<Box<dyn FnOnce() -> Result<(), Error>>> =
+            vec![Box::new(move || -> Result<(), Error> {
+                println!("removing: {:?}", cleanup_downloaded_zip);
+                fs::remove_file(cleanup_downloaded_zip)
+            })];
+
+        self.install_from_archive(
+            &downloaded_zip,
+            Some(source_ref),
+            do_install_viewer,
+            python_to_use,
+            cleanups,
+        )
+    }
+
+    ///
+    /// make an "installation" from an already downloaded branch archive
     ///
-    /// given branch name will be downloaded and "installed"  into base_path.
-    /// if do_install_viewer is true - viewer will also be installed
+    /// zip_path is expected to be an archive of the lifeblood repository,
+    /// exactly as github gives it for a branch, so it must carry full commit hash as the zip comment.
+    /// given archive is left untouched, the rest behaves the same as download_new_version,
+    /// including returning index of an already installed version with the same commit
+    /// and putting a copy of the archive into download cache
     ///
-    /// This operation will modify current base_dir, but will not switch current if it already exists
-    /// during the process a bunch of files will be downloaded and unzipped into system's temporary location
-    /// this will all be cleared on both success and failure
+    pub fn install_from_zip(
+        &mut self,
+        zip_path: &Path,
+        do_install_viewer: bool,
+        python_to_use: Option<&Path>,
+    ) -> Result<usize, InstallError> {
+        let result = if zip_path.is_file() {
+            println!(
+                "installing from archive {:?}, viewer too: {}",
+                zip_path, do_install_viewer
+            );
+            self.lock_base_dir().phase(InstallError::Locked).and_then(|_lock| {
+                self.install_from_archive(
+                    zip_path,
+                    None,
+                    do_install_viewer,
+                    python_to_use,
+                    Vec::new(),
+                )
+            })
+        } else {
+            Err(InstallError::Unpack(Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "given archive {:?} does not exist or is not a file",
+                    zip_path
+                ),
+            )))
+        };
+        let result = self.check_cancelled(result);
+        self.report_finished(result.as_ref().copied());
+        result
+    }
+
     ///
-    /// this is a long operation, as it involves downloading and installing a bunch of pip packages
+    /// common part of download_new_version and install_from_zip
     ///
-    pub fn download_new_version(
+    /// unpack given zip, install it and save metadata
+    /// given cleanups are performed on failure, as well as on success
+    ///
+    fn install_from_archive(
         &mut self,
-        branch_name: &str,
+        zip_file: &Path,
+        source_ref: Option<&SourceRef>,
         do_install_viewer: bool,
         python_to_use: Option<&Path>,
-    ) -> Result<usize, Error> {
-        macro_rules! noop {
-            ($($t:tt)*) => {};
-        }
-
+        mut cleanups: Vec<Box<dyn FnOnce() -> Result<(), Error>>>,
+    ) -> Result<usize, InstallError> {
         macro_rules! wraperr {
-            ($($phase:literal)|+, $call:expr, $cleanup:ident!) => {
+            ($call:expr, $cleanup:ident!) => {
                 match $call {
                     Ok(x) => x,
                     Err(e) => {
-
-                        $cleanup!($($phase)|+);
-
-                        return Err(Error::new(
-                            e.kind(),
-                            format!("{} failed: {}", concat!($($phase, ": "),+), e)
-                        ))
+                        $cleanup!();
+                        return Err(e);
                     }
                 }
             };
-            ($($phase:literal)|+, $call:expr) => {
-                wraperr!($($phase)|+, $call, noop!);
-            }
         }
 
-        let mut cleanups: Vec<Box<dyn FnOnce() -> Result<(), Error>>> = Vec::new();
-
         macro_rules! cleanup {
-            ($($preverr:literal)?) => {
+            () => {
+                // failed cleanup is not a reason to hide the error that made it needed
                 for (i, cleanup) in cleanups.into_iter().enumerate() {
                     println!("cleaning up: {}", i);
-                    wraperr!($($preverr |)?"cleanup phase", cleanup());
+                    if let Err(e) = cleanup() {
+                        eprintln!("cleanup {} failed: {}", i, e);
+                    }
                 }
             };
         }
 
-        let temp_location = std::env::temp_dir();
-
-        //
-        println!(
-            "downloading branch {}, viewer too: {}",
-            branch_name, do_install_viewer
-        );
-
-        //
-        // download phase
-        let downloaded_zip = wraperr!(
-            "download phase",
-            Self::helper_download(&temp_location, branch_name),
-            cleanup!
-        );
-        // add cleanup for downloaded stuff
-        let cleanup_downloaded_zip = downloaded_zip.clone();
-        cleanups.push(Box::new(|| -> Result<(), Error> {
-            println!("removing: {:?}", cleanup_downloaded_zip);
-            fs::remove_file(cleanup_downloaded_zip)
-        }));
+        let install_start = Instant::now();
 
         // create unzip dir
-        let unzip_location = downloaded_zip.with_extension("");
+        let unzip_location = std::env::temp_dir().join(helper_random_name());
         if let Err(e) = fs::create_dir(&unzip_location) {
             // cleanup
             cleanup!();
-            return Err(Error::new(
+            return Err(InstallError::Unpack(Error::new(
                 e.kind(),
                 format!("failed to create temp directory: {}", e),
-            ));
+            )));
         }
         // add cleanup for unzipped stuff
         let cleanup_unzip_location = unzip_location.clone();
@@ -532,14 +2745,43 @@ impl InstallationsData {
 
         //
         // unpacking phase
+        self.progress.phase(InstallPhase::Unpack);
         let (commit_full, date) = wraperr!(
-            "unpack phase",
-            Self::helper_unpack(&downloaded_zip, &unzip_location),
+            Self::helper_unpack(zip_file, &unzip_location, &self.progress, &self.cancel)
+                .phase(InstallError::Unpack),
             cleanup!
         );
-        let nice_name = &commit_full[..13];
+        wraperr!(self.cancel.check().phase(InstallError::Unpack), cleanup!);
+        let commit_full = commit_full.trim().to_owned();
+        if !is_valid_commit_hash(&commit_full) {
+            cleanup!();
+            return Err(InstallError::Unpack(Error::new(
+                std::io::ErrorKind::InvalidData,
+                "archive does not carry a commit hash, is it a github branch archive?",
+            )));
+        }
+        if let Some(SourceRef::Commit(commit)) = source_ref {
+            if !commit_full.starts_with(commit.as_str()) {
+                cleanup!();
+                return Err(InstallError::Unpack(Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "requested commit {}, but archive contains {}",
+                        commit, commit_full
+                    ),
+                )));
+            }
+        }
+        let nice_name = &commit_full[..NICE_NAME_LEN];
         // removing dir already added to cleanup
 
+        // keep archive for the future, failing that is not a reason to fail installation
+        if let Some(cache) = self.download_cache() {
+            if let Err(e) = cache.store(zip_file, &commit_full, source_ref) {
+                eprintln!("failed to put archive into download cache: {}", e);
+            }
+        }
+
         // check if already installed, maybe without viewer
         for (i, ver) in self.versions.iter().enumerate() {
             if ver.commit == commit_full && (ver.has_viewer || !do_install_viewer) {
@@ -548,53 +2790,110 @@ impl InstallationsData {
                 return Ok(i);
             }
         }
+        // installed, but without viewer - no need to reinstall everything
+        if let Some(i) = self.versions.iter().position(|x| x.commit == commit_full) {
+            println!("latest commit already downloaded, adding viewer");
+            wraperr!(self.helper_add_viewer(i, &unzip_location), cleanup!);
+            cleanup!();
+            return Ok(i);
+        }
 
         // install
         let dest_dir = self.base_path.join(&nice_name);
-        wraperr!("install phase", self.helper_install(&unzip_location, &dest_dir, do_install_viewer, python_to_use), cleanup!);
+        let (python_to_use, python_requires) = wraperr!(
+            self.helper_install(&unzip_location, &dest_dir, do_install_viewer, python_to_use),
+            cleanup!
+        );
 
         // println!("imitating error!");
         // cleanup!();
         // return Err(Error::new(std::io::ErrorKind::Other, "foo test!"));
 
+        // installation is done, temporary stuff is not needed any more
+        self.progress.phase(InstallPhase::Cleanup);
+        for (i, cleanup) in cleanups.into_iter().enumerate() {
+            println!("cleaning up: {}", i);
+            if let Err(e) = cleanup() {
+                eprintln!(
+                    "failed to cleanup temporary files, please remove them manually: {}",
+                    e
+                );
+            }
+        }
+
+        let metadata = VersionMetadata {
+            nice_name: nice_name.to_owned(),
+            commit: commit_full.clone(),
+            date,
+            source_url: source_ref.map(|x| self.download_source.archive_url(x)),
+            source_ref: source_ref.cloned(),
+            channel: match source_ref {
+                Some(SourceRef::Branch(x)) => Some(x.clone()),
+                _ => None,
+            },
+            python: Self::helper_get_venv_base_python(&dest_dir).or(python_to_use),
+            python_version: Self::helper_get_python_version(
+                &Self::helper_get_venv_relative_python_bin_path(&dest_dir),
+            ),
+            python_requires: (!python_requires.is_empty()).then(|| python_requires.to_string()),
+            has_viewer: Some(do_install_viewer),
+            manager_build: Some(BUILD_INFO.to_owned()),
+            install_duration_secs: Some(install_start.elapsed().as_secs_f64()),
+            notes: String::new(),
+        };
+        self.helper_register_version(dest_dir, metadata)
+    }
+
+    ///
+    /// helper func
+    ///
+    /// make shortcuts for, save metadata of and add to versions list a freshly installed version
+    ///
+    /// returns index of the new version
+    ///
+    fn helper_register_version(
+        &mut self,
+        dest_dir: PathBuf,
+        metadata: VersionMetadata,
+    ) -> Result<usize, InstallError> {
+        let do_install_viewer = metadata.has_viewer.unwrap_or(false);
+
         // (re)make shortcuts
 
         #[cfg(unix)]
         {
-            Self::helper_make_script_link("current", &self.base_path.join("lifeblood"), "")?;
+            Self::helper_make_script_link("current", &self.base_path.join("lifeblood"), "", true)
+                .phase(InstallError::Link)?;
             if do_install_viewer {
                 Self::helper_make_script_link(
                     "current",
                     &self.base_path.join("lifeblood_viewer"),
                     "viewer",
-                )?;
+                    true,
+                )
+                .phase(InstallError::Link)?;
             }
         }
 
         // save some metadata
 
-        helper_save_metadata(
-            &dest_dir.join("meta.info"),
-            &nice_name,
-            &commit_full,
-            date.into(),
-        )?;
+        metadata
+            .write_to_file(&dest_dir.join("meta.info"))
+            .phase(InstallError::Metadata)?;
 
         //
         // update versions list
+        let nice_name = metadata.nice_name.clone();
         let inserted_index = Self::insert_version_sorted_by_date(
             &mut self.versions,
-            InstalledVersion {
-                path: dest_dir,
-                nice_name: nice_name.to_owned(),
-                commit: commit_full,
-                date,
-                has_viewer: do_install_viewer,
-            },
+            InstalledVersion::from_metadata(dest_dir, nice_name, metadata),
         );
         if self.current_version != usize::MAX && inserted_index <= self.current_version {
             self.current_version += 1;
         }
+        if self.previous_version != usize::MAX && inserted_index <= self.previous_version {
+            self.previous_version += 1;
+        }
         //
 
         // last sanity check
@@ -602,17 +2901,24 @@ impl InstallationsData {
         // on windows links are privileged, so we use lifeblood.cmd pointing directly to the commit
         #[cfg(unix)]
         if !self.base_path.join("current").exists() {
-            wraperr!(
-                "create 'current' link",
-                self.make_version_current_unix(inserted_index)
-            );
+            self.make_version_current_unix(inserted_index, "current")
+                .phase(InstallError::Link)?;
         }
         #[cfg(windows)]
         if !self.base_path.join("lifeblood.cmd").exists() {
-            wraperr!(
-                "create 'current' link",
-                self.make_version_current_win(inserted_index, do_install_viewer)
-            );
+            self.make_version_current_win(inserted_index, do_install_viewer)
+                .phase(InstallError::Link)?;
+        }
+        // same for version's channel
+        if let Some(channel) = self.versions[inserted_index].channel.clone() {
+            if self.channel_current_version_index(&channel).is_none() {
+                if let Err(e) = self.helper_make_version_channel_current(inserted_index) {
+                    eprintln!(
+                        "failed to make version current of channel {}: {}",
+                        channel, e
+                    );
+                }
+            }
         }
 
         Ok(inserted_index)
@@ -637,76 +2943,187 @@ impl InstallationsData {
     ///
     /// helper func
     ///
-    /// download latest commit
+    /// download archive of given ref
     ///
-    fn helper_download(download_location: &Path, branch_name: &str) -> Result<PathBuf, Error> {
-        let mut downloader = match Downloader::builder()
-            .download_folder(&download_location)
-            .connect_timeout(std::time::Duration::from_secs(90))
-            .build()
-        {
-            Ok(x) => x,
-            Err(e) => {
-                // nothing to cleanup
-                return Err(Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("could not initialize downloader :( {}", e),
-                ));
-            }
-        };
+    fn helper_download(
+        download_location: &Path,
+        download_source: &DownloadSource,
+        source_ref: &SourceRef,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<PathBuf, Error> {
+        // nothing to cleanup if this fails
+        let downloader = download_source.build_downloader(
+            download_location,
+            std::time::Duration::from_secs(90),
+            std::time::Duration::from_secs(300),
+            3,
+        )?;
 
-        let mut rng = thread_rng();
-        let url = format!(
-            "https://github.com/pedohorse/lifeblood/archive/refs/heads/{}.zip",
-            branch_name
-        );
+        let url = download_source.archive_url(source_ref);
+        let temp_filename: PathBuf = PathBuf::from(helper_random_name());
 
-        let temp_filename: PathBuf = PathBuf::from(
-            (0..16)
-                .map(|_| rng.sample(Alphanumeric) as char)
-                .collect::<String>(),
-        );
+        Self::helper_download_url(
+            downloader,
+            &url,
+            &temp_filename.with_extension("zip"),
+            progress,
+            cancel,
+        )
+    }
+
+    ///
+    /// helper func
+    ///
+    /// download given url into a file with given name in downloader's location
+    ///
+    /// downloader itself cannot be interrupted, so it's run in a thread,
+    /// and if cancelled - it is left to finish in background and clean up after itself
+    ///
+    fn helper_download_url(
+        mut downloader: Downloader,
+        url: &str,
+        target_filepath: &Path,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<PathBuf, Error> {
+        println!("downloading {}", url);
 
         // download
-        let target_filepath = temp_filename.with_extension("zip");
-        let downloaded_zip =
-            match downloader.download(&[Download::new(&url).file_name(&target_filepath)]) {
-                Ok(results) => {
-                    let mut path = PathBuf::new();
-                    for part in results {
-                        match part {
-                            Ok(summary) => {
-                                println!("Ok: downloaded {:?}", summary);
-                                path = summary.file_name;
-                                break;
-                            }
-                            Err(e) => {
-                                // cleanup
-                                if target_filepath.exists() {
-                                    fs::remove_file(target_filepath)?;
-                                }
-                                return Err(Error::new(
-                                    std::io::ErrorKind::Other,
-                                    format!("download failed: {:?}", e),
-                                ));
-                            }
-                        }
-                    }
-                    path
+        let download = Download::new(url)
+            .file_name(target_filepath)
+            .progress(progress.download_reporter());
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let result = downloader.download(&[download]);
+            // no one is waiting for it any more
+            if let Err(mpsc::SendError(Ok(results))) = sender.send(result) {
+                for summary in results.into_iter().flatten() {
+                    fs::remove_file(&summary.file_name).ok();
                 }
-                Err(e) => {
-                    // cleanup
-                    if target_filepath.exists() {
-                        fs::remove_file(target_filepath)?;
-                    }
+            }
+        });
+        let result = loop {
+            match receiver.recv_timeout(Duration::from_millis(200)) {
+                Ok(x) => break x,
+                Err(mpsc::RecvTimeoutError::Timeout) => cancel.check()?,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
                     return Err(Error::new(
                         std::io::ErrorKind::Other,
-                        format!("download failed: {:?}", e),
+                        "download failed: downloader thread crashed",
                     ));
                 }
-            };
+            }
+        };
+        let downloaded = match result {
+            Ok(results) => {
+                let mut path = PathBuf::new();
+                for part in results {
+                    match part {
+                        Ok(summary) => {
+                            println!("Ok: downloaded {:?}", summary);
+                            path = summary.file_name;
+                            break;
+                        }
+                        Err(e) => {
+                            // cleanup
+                            if target_filepath.exists() {
+                                fs::remove_file(target_filepath)?;
+                            }
+                            return Err(Error::new(
+                                std::io::ErrorKind::Other,
+                                format!("download failed: {:?}", e),
+                            ));
+                        }
+                    }
+                }
+                path
+            }
+            Err(e) => {
+                // cleanup
+                if target_filepath.exists() {
+                    fs::remove_file(target_filepath)?;
+                }
+                return Err(Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("download failed: {:?}", e),
+                ));
+            }
+        };
+
+        Ok(downloaded)
+    }
+
+    ///
+    /// helper func
+    ///
+    /// ask download source what's the head commit of given ref and when it was made
+    ///
+    fn helper_get_head_commit(
+        download_location: &Path,
+        download_source: &DownloadSource,
+        source_ref: &SourceRef,
+        cancel: &CancellationToken,
+    ) -> Result<(String, DateTime<Utc>), Error> {
+        if !download_source.has_commit_info() {
+            return Err(Error::new(
+                std::io::ErrorKind::Unsupported,
+                "download source has no commit information url",
+            ));
+        }
+        let downloader = download_source.build_downloader(
+            download_location,
+            std::time::Duration::from_secs(30),
+            std::time::Duration::from_secs(60),
+            3,
+        )?;
+
+        let url = download_source.commit_info_url(source_ref);
+        let temp_filename: PathBuf = PathBuf::from(helper_random_name());
+        let info_file = Self::helper_download_url(
+            downloader,
+            &url,
+            &temp_filename.with_extension("json"),
+            &ProgressReporter::default(),
+            cancel,
+        )?;
+        let info_text = fs::read_to_string(&info_file);
+        if let Err(e) = fs::remove_file(&info_file) {
+            eprintln!("failed to remove {:?}: {}", info_file, e);
+        }
+
+        let info: serde_json::Value = match serde_json::from_str(&info_text?) {
+            Ok(x) => x,
+            Err(e) => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("commit info is not a valid json: {}", e),
+                ));
+            }
+        };
+        let commit = match info["sha"].as_str() {
+            Some(x) => x.to_owned(),
+            None => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "commit info does not contain commit hash",
+                ));
+            }
+        };
+        let date = match info["commit"]["committer"]["date"]
+            .as_str()
+            .map(DateTime::parse_from_rfc3339)
+        {
+            Some(Ok(x)) => x.with_timezone(&Utc),
+            _ => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "commit info does not contain a valid commit date",
+                ));
+            }
+        };
 
-        Ok(downloaded_zip)
+        Ok((commit, date))
     }
 
     ///
@@ -717,6 +3134,8 @@ impl InstallationsData {
     fn helper_unpack(
         zip_file: &Path,
         unzip_location: &Path,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
     ) -> Result<(String, DateTime<Utc>), Error> {
         let reader = BufReader::new(match File::open(zip_file) {
             Ok(x) => x,
@@ -759,40 +3178,95 @@ impl InstallationsData {
         };
 
         // actual unzip
-        if let Err(e) = zip_reader.extract(unzip_location) {
+        // same as ZipArchive::extract does, but file by file to report progress
+        let total = zip_reader.len();
+        for i in 0..total {
             // cleanup partially unzipped stuff ?
             // for now it's left to the caller to cleanup
-            return Err(Error::new(
-                std::io::ErrorKind::Other,
-                format!("unzip failed: {}", e),
-            ));
+            cancel.check()?;
+            if let Err(e) = Self::helper_unpack_file(&mut zip_reader, i, unzip_location) {
+                return Err(Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("unzip failed: {}", e),
+                ));
+            }
+            progress.report(InstallEvent::Unpack { done: i + 1, total });
         }
 
         Ok((comment, date))
     }
 
+    ///
+    /// helper func
+    ///
+    /// unpack a single file with given index from zip archive
+    ///
+    fn helper_unpack_file<R: Read + Seek>(
+        zip_reader: &mut ZipArchive<R>,
+        index: usize,
+        unzip_location: &Path,
+    ) -> Result<(), Error> {
+        let mut file = match zip_reader.by_index(index) {
+            Ok(x) => x,
+            Err(e) => return Err(Error::new(std::io::ErrorKind::InvalidData, e)),
+        };
+        let out_path = match file.enclosed_name() {
+            Some(x) => unzip_location.join(x),
+            None => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("invalid file path in archive: {}", file.name()),
+                ));
+            }
+        };
+
+        if file.name().ends_with('/') {
+            fs::create_dir_all(&out_path)?;
+        } else {
+            if let Some(parent) = out_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut out_file = File::create(&out_path)?;
+            std::io::copy(&mut file, &mut out_file)?;
+        }
+        #[cfg(unix)]
+        if let Some(mode) = file.unix_mode() {
+            fs::set_permissions(&out_path, fs::Permissions::from_mode(mode))?;
+        }
+
+        Ok(())
+    }
+
     ///
     /// helper func
     ///
     /// "install" the whole thing
     /// create all dirs, venv, copy stuff, etc
     ///
+    /// returns python that was used, as it may be chosen here,
+    /// and pythons supported by the package, to be checked against if venv is ever remade
+    ///
     fn helper_install(
         &mut self,
         unzip_location: &Path,
         dest_dir: &Path,
         do_install_viewer: bool,
         python_to_use: Option<&Path>,
-    ) -> Result<(), Error> {
+    ) -> Result<(Option<PathBuf>, VersionSpecifiers), InstallError> {
         let mut existing_dest: Option<PathBuf> = None;
 
         macro_rules! wraperr {
             ($text:literal, $call:expr) => {
+                wraperr!($call
+                    .map_err(|e| Error::new(e.kind(), format!("{} failed: {}", $text, e)))
+                    .phase(InstallError::Other))
+            };
+            ($call:expr) => {
                 match $call {
                     Ok(x) => x,
                     Err(e) => {
                         error_cleanup!();
-                        return Err(Error::new(e.kind(), format!("{} failed: {}", $text, e)));
+                        return Err(e);
                     }
                 }
             };
@@ -803,14 +3277,16 @@ impl InstallationsData {
                 if let Ok("1") = std::env::var("LBMANAGER_DEBUG_KEEP_INSTALL").as_deref() {
                     println!("keeping partial install dir, as debug env is set");
                 } else {
+                    // failed cleanup is not a reason to hide the error that made it needed
                     if dest_dir.exists() {
-                        fs::remove_dir_all(&dest_dir)?; // sloppy error report
+                        if let Err(e) = fs::remove_dir_all(&dest_dir) {
+                            eprintln!("failed to remove partial install {:?}: {}", dest_dir, e);
+                        }
                     }
-                    match existing_dest {
-                        Some(path) => {
-                            fs::rename(&path, &dest_dir)?; // sloppy error report
+                    if let Some(path) = existing_dest {
+                        if let Err(e) = fs::rename(&path, &dest_dir) {
+                            eprintln!("failed to put {:?} back from {:?}: {}", dest_dir, path, e);
                         }
-                        None => (),
                     }
                 }
             };
@@ -830,10 +3306,10 @@ impl InstallationsData {
 
         if let Err(e) = fs::create_dir(&dest_dir) {
             error_cleanup!();
-            return Err(Error::new(
+            return Err(InstallError::Other(Error::new(
                 std::io::ErrorKind::Other,
                 format!("failed to create destination dir: {}", e),
-            ));
+            )));
         }
 
         let inner_dir = match unzip_location.read_dir() {
@@ -843,22 +3319,24 @@ impl InstallationsData {
                     dir.path()
                 } else {
                     error_cleanup!();
-                    return Err(Error::new(
+                    return Err(InstallError::Unpack(Error::new(
                         std::io::ErrorKind::Other,
                         "no dir suitable found in unzip location",
-                    ));
+                    )));
                 }
             }
             Err(e) => {
                 error_cleanup!();
-                return Err(Error::new(
+                return Err(InstallError::Unpack(Error::new(
                     e.kind(),
                     format!("error reading unpack dir: {}", e),
-                ));
+                )));
             }
         };
 
         // copy modules
+        wraperr!("copying modules", self.cancel.check());
+        self.progress.phase(InstallPhase::CopyModules);
         wraperr!(
             "copying modules",
             Self::helper_copy_dir(&inner_dir.join("src").join("lifeblood"), dest_dir)
@@ -872,74 +3350,74 @@ impl InstallationsData {
 
         if let Err(e) = fs::copy(inner_dir.join("entry.py"), dest_dir.join("entry.py")) {
             error_cleanup!();
-            return Err(Error::new(
+            return Err(InstallError::Other(Error::new(
                 std::io::ErrorKind::Other,
                 format!("failed to copy unzipped contents: {}", e),
-            ));
+            )));
         }
 
         // do the venv
 
         // prepare requirements
         let requirements_path = dest_dir.join("requirements.txt");
-        let reqs = wraperr!(
-            "getting reqs",
-            Self::helper_get_requirements_from_setupcfg(
-                &inner_dir.join("pkg_lifeblood").join("setup.cfg"),
-            )
-        );
-        wraperr!(
-            "writing reqs",
-            Self::helper_write_strings_to_file(reqs, &requirements_path)
-        );
+        let metadata = wraperr!(PackageMetadata::from_package_dir(&inner_dir.join("pkg_lifeblood"))
+            .phase(InstallError::Requirements));
+        wraperr!(Self::helper_write_strings_to_file(metadata.requirements, &requirements_path)
+            .phase(InstallError::Requirements));
 
         let requirements_path_viewer = dest_dir.join("requirements_viewer.txt");
-        let reqs = wraperr!(
-            "getting reqs",
-            Self::helper_get_requirements_from_setupcfg(
-                &inner_dir.join("pkg_lifeblood_viewer").join("setup.cfg"),
-            )
-        );
-        wraperr!(
-            "writing reqs",
-            Self::helper_write_strings_to_file(reqs, &requirements_path_viewer)
-        );
-
-        let supported_python_versions = wraperr!(
-            "getting supported python versions",
-            Self::helper_get_pyvers_from_setupcfg(
-                &inner_dir.join("pkg_lifeblood").join("setup.cfg"),
-            )
-        );
-
-        // Self::helper_get_python_command(supported_python_versions)
-        if let Some(python_command) = python_to_use {
-            if let Ok(true) = Self::helper_is_python_version_supported(python_command, &supported_python_versions) {
-            } else {
-                return Err(Error::new(
-                    std::io::ErrorKind::Unsupported,
-                    format!(
-                        "given python version is not supported. supported: {}",
-                        supported_python_versions
-                            .iter()
-                            .map(|x| format!("{}.{}", x.0, x.1))
-                            .collect::<Vec<String>>()
-                            .join(", ")
-                    ),
-                ))
+        let viewer_metadata = wraperr!(PackageMetadata::from_package_dir(
+            &inner_dir.join("pkg_lifeblood_viewer")
+        )
+        .phase(InstallError::Requirements));
+        wraperr!(Self::helper_write_strings_to_file(
+            viewer_metadata.requirements,
+            &requirements_path_viewer
+        )
+        .phase(InstallError::Requirements));
+
+        let python_to_use =
+            wraperr!(self.helper_resolve_python(python_to_use, &metadata.python_requires));
+        let python_to_use = python_to_use.as_deref();
+
+        // given lock is kept with the version, as it's what the venv is made of
+        let lock_path = dest_dir.join(LOCK_FILE_NAME);
+        let constraints_path = match &self.requirements_lock {
+            Some(given_lock) => {
+                println!("installing according to lock file {:?}", given_lock);
+                wraperr!(fs::copy(given_lock, &lock_path)
+                    .map_err(|e| Error::new(
+                        e.kind(),
+                        format!("failed to copy lock file {:?}: {}", given_lock, e)
+                    ))
+                    .phase(InstallError::Requirements));
+                Some(lock_path.as_path())
             }
-        }
+            None => None,
+        };
 
-        wraperr!(
-            "installing to venv",
-            Self::helper_install_venv(&dest_dir, &requirements_path, python_to_use)
-        );
+        wraperr!("installing to venv", self.cancel.check());
+        wraperr!(Self::helper_install_venv(
+            &dest_dir,
+            &requirements_path,
+            constraints_path,
+            python_to_use,
+            &self.installer_settings,
+            &self.progress,
+            &self.cancel,
+        ));
         if do_install_viewer {
-            wraperr!(
-                "installing to venv",
-                Self::helper_install_venv(&dest_dir, &requirements_path_viewer, python_to_use)
-            );
+            wraperr!(Self::helper_install_venv(
+                &dest_dir,
+                &requirements_path_viewer,
+                constraints_path,
+                python_to_use,
+                &self.installer_settings,
+                &self.progress,
+                &self.cancel,
+            ));
         }
+        Self::helper_record_lock_file(dest_dir);
 
         // all good, cleanup temp dir if used
         if let Some(path) = existing_dest {
@@ -948,123 +3426,104 @@ impl InstallationsData {
             }
         }
 
-        Ok(())
+        Ok((python_to_use.map(|x| x.to_path_buf()), metadata.python_requires))
     }
 
     ///
     /// helper func
     ///
-    /// copy dirs, with overwriting
+    /// python to create venv with: given one, if it satisfies given requirements,
+    /// or, if none is given and auto selection is on, the newest suitable one found
     ///
-    fn helper_copy_dir(src: &Path, dest: &Path) -> Result<(), Error> {
-        let mut copy_options = CopyOptions::new();
-        copy_options.overwrite = true;
-        if let Err(e) = fs_extra::dir::copy(src, dest, &copy_options) {
-            // TODO: cleanup
-            return Err(Error::new(
-                std::io::ErrorKind::Other,
-                format!("failed to copy unzipped contents: {}", e),
-            ));
+    /// None means embedded python on windows
+    ///
+    fn helper_resolve_python(
+        &self,
+        python_to_use: Option<&Path>,
+        python_requires: &VersionSpecifiers,
+    ) -> Result<Option<PathBuf>, InstallError> {
+        let python_to_use = match python_to_use {
+            None if self.auto_select_python => {
+                let chosen = Self::helper_choose_python(python_requires);
+                // on windows there is still embedded python to fall back to
+                if chosen.is_none() && !cfg!(windows) {
+                    return Err(InstallError::UnsupportedPython {
+                        version: None,
+                        requires: python_requires.to_string(),
+                    });
+                }
+                chosen
+            }
+            x => x.map(|x| x.to_path_buf()),
         };
 
-        Ok(())
+        if let Some(python_command) = &python_to_use {
+            let version = Self::helper_get_python_version(python_command);
+            let is_supported = version
+                .as_deref()
+                .and_then(PythonVersion::interpreter_release)
+                .is_some_and(|x| python_requires.contains(&x));
+            if !is_supported {
+                eprintln!("python version {:?} is not supported", version);
+                return Err(InstallError::UnsupportedPython {
+                    version,
+                    requires: python_requires.to_string(),
+                });
+            }
+        }
+        Ok(python_to_use)
     }
 
     ///
     /// helper func
     ///
-    /// somewhat parse setup.cfg and get requirements
+    /// find the newest python on this machine that satisfies given requirements
     ///
-    fn helper_get_requirements_from_setupcfg(path: &Path) -> Result<Vec<String>, Error> {
-        let mut config_cfg = String::new();
-        match fs::File::open(path) {
-            Ok(mut file) => {
-                if let Err(e) = file.read_to_string(&mut config_cfg) {
-                    // TODO: cleanup
-                    return Err(Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("failed to open setup.cfg: {}", e),
-                    ));
-                }
+    fn helper_choose_python(python_requires: &VersionSpecifiers) -> Option<PathBuf> {
+        let discovery = PythonDiscovery::run();
+        match discovery.choose(python_requires) {
+            Some(candidate) => {
+                println!(
+                    "using python {} found at {:?} ({})",
+                    candidate.version().unwrap_or("?"),
+                    candidate.path(),
+                    candidate.source()
+                );
+                Some(candidate.path().to_path_buf())
             }
-            Err(e) => {
-                // TODO: cleanup
-                return Err(Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("failed to open setup.cfg: {}", e),
-                ));
-            }
-        };
-
-        // a bit hacky way of getting info from setup.cfg, as it's not really a toml, and i'm not sure about it's syntax
-        let mut reqs = Vec::new();
-        let mut found_start = false;
-        for line in config_cfg.lines() {
-            if found_start {
-                let line = line.trim();
-                if line.len() == 0 || line.starts_with("[") {
-                    break;
-                }
-                // skip deps on self
-                if !line.starts_with("lifeblood") {
-                    reqs.push(line.to_owned());
+            None => {
+                eprintln!("no python satisfying {} found:", python_requires);
+                for candidate in discovery.candidates() {
+                    eprintln!(
+                        "    {:?}: {}",
+                        candidate.path(),
+                        candidate
+                            .rejection_reason(python_requires)
+                            .unwrap_or_default()
+                    );
                 }
-            } else {
-                let line = line.trim();
-                found_start = line == "install_requires="
-                    || line.starts_with("install_requires ") && line.ends_with("=");
+                None
             }
         }
-
-        Ok(reqs)
     }
 
     ///
     /// helper func
-    /// 
-    /// extracts supported python versions from given setup.cfg file
-    /// 
-    fn helper_get_pyvers_from_setupcfg(path: &Path) -> Result<Vec<(u32, u32)>, Error> {
-        let mut buffer = String::new();
-        match fs::File::open(path) {
-            Ok(mut file) => {
-                if let Err(e) = file.read_to_string(&mut buffer) {
-                    eprintln!("failed to read setup.cfg");
-                    return Err(e)
-                }
-            },
-            Err(e) => {
-                eprintln!("failed to read setup.cfg");
-                return Err(e);
-            }
+    ///
+    /// copy dirs, with overwriting
+    ///
+    fn helper_copy_dir(src: &Path, dest: &Path) -> Result<(), Error> {
+        let mut copy_options = CopyOptions::new();
+        copy_options.overwrite = true;
+        if let Err(e) = fs_extra::dir::copy(src, dest, &copy_options) {
+            // TODO: cleanup
+            return Err(Error::new(
+                std::io::ErrorKind::Other,
+                format!("failed to copy unzipped contents: {}", e),
+            ));
         };
 
-        let mut supported_vers = Vec::new();
-
-        for line in buffer.lines() {
-            let line = line.trim();
-            if let Some((key, val)) = line.split_once("=") {
-                if key.trim() != "python_requires" {
-                    continue;
-                }
-
-                if let Ok(ver_req) = VersionReq::parse(val.trim()) {
-                    for ver in (8..30 as u64).map(|x| Version::new(3, x, 0)) {
-                        if ver_req.matches(&ver) {
-                            supported_vers.push((ver.major as u32, ver.minor as u32));
-                        }
-                    }
-                    break;
-                } else {
-                    return Err(Error::new(
-                            std::io::ErrorKind::InvalidData,
-                            "failed to parse python_requires from setup.cfg"
-                        ))
-                }
-            }
-        }
-
-        return Ok(supported_vers);
+        Ok(())
     }
 
     ///
@@ -1099,27 +3558,13 @@ impl InstallationsData {
 
     ///
     /// helper func
-    /// 
-    /// create venv, just that
-    /// 
-    fn helper_initialize_venv(dest_dir: &Path, python_command: &Path) -> Result<(), Error> {
+    ///
+    /// (re)write pth file into venv's site packages,
+    /// so that venv python sees lifeblood modules in dest_dir
+    ///
+    fn helper_write_pth_file(dest_dir: &Path) -> Result<(), Error> {
         let venv_pybin_path = Self::helper_get_venv_relative_python_bin_path(dest_dir);
 
-        let exit_status = match process::Command::new(python_command)
-                    .current_dir(dest_dir)
-                    .arg("-m")
-                    .arg("venv")
-                    .arg("venv")
-                    .status()
-        {
-            Ok(status) => status,
-            Err(e) => {
-                return Err(Error::new(e.kind(), format!("error running python: {}", e)));
-            }
-        };
-        check_status!(exit_status);
-
-        // write pth file
         // TODO: maybe write to every path returned by getsitepackages ?
         let site_path = match process::Command::new(&venv_pybin_path)
             .current_dir(dest_dir)
@@ -1159,6 +3604,7 @@ impl InstallationsData {
         let mut py_pth_file = fs::OpenOptions::new()
             .write(true)
             .create(true)
+            .truncate(true)
             .open(site_path.join("lifeblood.pth"))?;
         writeln!(
             py_pth_file,
@@ -1173,19 +3619,61 @@ impl InstallationsData {
         return Ok(());
     }
 
+    ///
+    /// helper func
+    ///
+    /// record exact versions of packages installed into version's venv into its lock file
+    ///
+    /// failing that is not a reason to fail installation, version is just left without a lock
+    ///
+    fn helper_record_lock_file(dest_dir: &Path) {
+        let result = Self::helper_freeze_venv(dest_dir).and_then(|lock| {
+            Self::helper_write_file_atomically(&dest_dir.join(LOCK_FILE_NAME), &lock)
+        });
+        if let Err(e) = result {
+            eprintln!("failed to record installed packages into lock file: {}", e);
+        }
+    }
+
+    ///
+    /// helper func
+    ///
+    /// pip freeze output for version's venv
+    ///
+    fn helper_freeze_venv(dest_dir: &Path) -> Result<Vec<u8>, Error> {
+        let dest_dir = dunce::simplified(dest_dir);
+        let venv_pybin_path = Self::helper_get_venv_relative_python_bin_path(dest_dir);
+        let output = process::Command::new(&venv_pybin_path)
+            .current_dir(dest_dir)
+            .arg("-m")
+            .arg("pip")
+            .arg("freeze")
+            .output()?;
+        let exit_status = output.status;
+        check_status!(exit_status);
+        Ok(output.stdout)
+    }
+
     ///
     /// helper func
     ///
     /// install venv phase
     ///
-    /// for unix it assumes that given python has venv module available
-    /// so it will use that to create proper venv
+    /// venv is created and requirements are installed by the installer chosen by settings
     ///
     /// on windows extra step will be added - in canse python is not available -
     /// it will be downloaded, minimal python venv will be handcrafted,
     /// get_pip will be used to get pip
     ///
-    fn helper_install_venv(dest_dir: &Path, requirements_path: &Path, python_to_use: Option<&Path>) -> Result<(), Error> {
+    fn helper_install_venv(
+        dest_dir: &Path,
+        requirements_path: &Path,
+        constraints_path: Option<&Path>,
+        python_to_use: Option<&Path>,
+        installer_settings: &InstallerSettings,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<(), InstallError> {
         // if venv dir is present - skip creating venv
 
         // in case of windows and "verbatim" paths - it seems that some parts of python,
@@ -1194,42 +3682,45 @@ impl InstallationsData {
         let dest_dir = dunce::simplified(dest_dir);
 
         let venv_pybin_path = Self::helper_get_venv_relative_python_bin_path(dest_dir);
+        let installer = installer_settings.installer().phase(InstallError::Venv)?;
+        println!("using {} installer", installer.name());
 
         if !dest_dir.join("venv").exists() {
+            progress.phase(InstallPhase::CreateVenv);
+            cancel.check()?;
             if let Some(python_command) = python_to_use {
-                Self::helper_initialize_venv(dest_dir, &python_command)?;
+                installer
+                    .create_venv(dest_dir, python_command, progress, cancel)
+                    .phase(InstallError::Venv)?;
+                Self::helper_write_pth_file(dest_dir).phase(InstallError::Venv)?;
             } else {
                 // python not found, but we know what to do on windows in this case
                 if cfg!(windows) {
-                    Self::helper_prepare_windows_venv(dest_dir)?
+                    let index_args = installer_settings.pip_index_args();
+                    Self::helper_prepare_windows_venv(dest_dir, &index_args)
+                        .phase(InstallError::Venv)?
                 } else {
-                    return Err(Error::new(
+                    return Err(InstallError::Venv(Error::new(
                         std::io::ErrorKind::NotFound,
                         "python cannot be automatically installed on this platform. Please install python system-wide or provide a custom one with PYTHON_BIN",
-                    ));
+                    )));
                 }
             }
         }
         println!("venv python at {:?}", venv_pybin_path);
 
         // run pip
-        let exit_status = match process::Command::new(&venv_pybin_path)
-            .current_dir(dest_dir)
-            .arg("-m")
-            .arg("pip")
-            .arg("install")
-            .arg("-r")
-            .arg(requirements_path)
-            .status()
-        {
-            Ok(status) => status,
-            Err(e) => {
-                return Err(Error::new(e.kind(), format!("error running pip: {}", e)));
-            }
-        };
-        check_status!(exit_status);
-
-        Ok(())
+        progress.phase(InstallPhase::InstallRequirements);
+        installer
+            .install_requirements(
+                dest_dir,
+                &venv_pybin_path,
+                requirements_path,
+                constraints_path,
+                progress,
+                cancel,
+            )
+            .phase(InstallError::Pip)
     }
 
     ///
@@ -1306,9 +3797,10 @@ impl InstallationsData {
     /// windows-specific venv creation
     /// uses special embedded python
     /// manually creates bare minimum for python to understand it's in venv
-    /// installs pip with get-pip.py special script from pypi
+    /// installs pip with get-pip.py special script from pypi,
+    /// pip itself is taken from where given pip index args say
     ///
-    fn helper_prepare_windows_venv(dest_dir: &Path) -> Result<(), Error> {
+    fn helper_prepare_windows_venv(dest_dir: &Path, index_args: &[OsString]) -> Result<(), Error> {
         let pyver = "3.10.9"; // TODO: do not hardcode
         let pycode = "310";
         //
@@ -1331,7 +3823,12 @@ impl InstallationsData {
         if !venv_bin_path.exists() {
             fs::create_dir(&venv_bin_path)?;
         }
-        Self::helper_unpack(&pyzip, &venv_bin_path)?;
+        Self::helper_unpack(
+            &pyzip,
+            &venv_bin_path,
+            &ProgressReporter::default(),
+            &CancellationToken::default(),
+        )?;
 
         // get pip.pyz
         let getpip = Self::helper_download_single_file(
@@ -1376,6 +3873,7 @@ impl InstallationsData {
         let exit_status = match process::Command::new(venv_bin_path.join("python"))
             .current_dir(dest_dir)
             .arg(getpip)
+            .args(index_args)
             .status()
         {
             Ok(status) => status,
@@ -1390,71 +3888,67 @@ impl InstallationsData {
 
     ///
     /// helper func
-    /// 
-    /// tests if given python is of supported version
-    /// (checks only MAJOR.MINOR versions)
-    /// 
-    fn helper_is_python_version_supported(python_bin: &Path, supported_python_versions: &[(u32, u32)]) -> Result<bool, Error> {
-        match process::Command::new(&python_bin).arg("--version").output() {
-            Ok(output) => {
-                if let Some(code) = output.status.code() {
-                    #[cfg(windows)]
-                    if code == 9009 {
-                        // no idea - special windows exic tode meaning command not found?
-                        return Err(Error::new(
-                            std::io::ErrorKind::NotFound,
-                            "failed to launch given python binary"
-                        ));
-                    }
-                    // otherwise - pass
-                } else {
-                    return Err(Error::new(
-                        std::io::ErrorKind::NotFound,
-                        "failed to launch given python binary"
-                    ));
-                }
-                // check python version compatibility
-                // in case anything goes not as expected - return None meaning version incompatible
-                if let Ok(ver_str) = str::from_utf8(&output.stdout) {
-                    // ver_str is expected to be of a form "Python X.Y.Z"
-                    // NOTE that Z may contain not just digits. and overall we should only rely on X.Y
-                    if let Some((_, suf)) = ver_str.split_once(' ') {
-                        // suf should be the X.Y.Z
-                        if let Ok(ver_parts) = suf.split('.').take(2).map(|s| u32::from_str_radix(s, 10)).collect::<Result<Vec<u32>, _>>() {
-                            if ver_parts.len() != 2 {
-                                eprintln!("python version {:?} is not supported", suf);
-                                return Ok(false)
-                            }
-                            let ver_tuple = (ver_parts[0], ver_parts[1]);
-                            if !supported_python_versions.iter().any(|v| *v == ver_tuple) {
-                                eprintln!("python version {:?} is not supported", ver_tuple);
-                                return Ok(false)
-                            }
-                        } else {
-                            eprintln!("python version {:?} is not supported", suf);
-                            return Ok(false)
-                        };
-                    } else {
-                        eprintln!("failed to parse python version {:?}", ver_str);
-                        return Err(Error::new(
-                            std::io::ErrorKind::Other,
-                            "unexpected python call output"
-                        ))
-                    }
-                } else {
-                    return Err(Error::new(
-                        std::io::ErrorKind::Other,
-                        "failed to parse python call output"
-                    ))
-                }
+    ///
+    /// version of given python as "X.Y.Z", None if it cannot be figured out
+    ///
+    fn helper_get_python_version(python_bin: &Path) -> Option<String> {
+        get_python_version(python_bin).ok()
+    }
+
+    ///
+    /// helper func
+    ///
+    /// python that venv in given version dir was actually made with, as venv itself remembers it,
+    /// None if venv's pyvenv.cfg cannot be read
+    ///
+    fn helper_get_venv_base_python(dest_dir: &Path) -> Option<PathBuf> {
+        let text = fs::read_to_string(dest_dir.join("venv").join("pyvenv.cfg")).ok()?;
+        let mut home = None;
+        for line in text.lines() {
+            let (key, value) = match line.split_once('=') {
+                Some(x) => x,
+                None => continue,
+            };
+            match key.trim() {
+                // only newer pythons write the executable itself
+                "executable" => return Some(PathBuf::from(value.trim())),
+                "home" => home = Some(PathBuf::from(value.trim())),
+                _ => (),
             }
-            Err(_) => return Err(Error::new(
-                std::io::ErrorKind::NotFound,
-                "failed to launch given python binary"
-            )),
-        };
+        }
+        // home is the dir of the python executable
+        let python_name = if cfg!(windows) { "python.exe" } else { "python3" };
+        home.map(|x| x.join(python_name))
+    }
 
-        Ok(true)
+    ///
+    /// helper func
+    ///
+    /// run given python with given args inside given dir, used by version verification
+    /// returns if it succeeded, and its output worth showing
+    ///
+    fn helper_run_python_check(dir: &Path, python_bin: &Path, args: &[&str]) -> (bool, String) {
+        let output = match process::Command::new(python_bin)
+            .current_dir(dir)
+            .args(args)
+            .output()
+        {
+            Ok(x) => x,
+            Err(e) => {
+                return (false, format!("failed to run python: {}", e));
+            }
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            return (true, stdout.trim().to_owned());
+        }
+        // for tracebacks the last line is the one that matters
+        let details = match stderr.trim().lines().last() {
+            Some(line) => line.to_owned(),
+            None => stdout.trim().to_owned(),
+        };
+        (false, details)
     }
 
     ///
@@ -1463,20 +3957,35 @@ impl InstallationsData {
     /// make common lifeblood link files
     /// used to create lifeblood, lifeblood_viewer
     ///
+    /// per_host makes unix scripts prefer this host's own <current_name>@<hostname> link,
+    /// it has no effect on windows
+    ///
     fn helper_make_script_link(
         current_name: &str,
         file_path: &Path,
         entry_arg: &str,
+        per_host: bool,
     ) -> Result<(), Error> {
         #[cfg(windows)]
         let file_path = &file_path.with_extension("cmd");
 
-        let contents = if cfg!(unix) {
+        let contents = if cfg!(unix) && !per_host {
+            format!(
+                "#!/bin/sh\n\
+                 cwd=`dirname \\`readlink -f $0\\``\n\
+                 current=$cwd/{}\n\
+                 exec $current/venv/{}/python $current/entry.py {} \"$@\"",
+                current_name, VENV_BIN, entry_arg
+            )
+        } else if cfg!(unix) {
+            // this host's own current link is used if there is one
             format!(
                 "#!/bin/sh\n\
                  cwd=`dirname \\`readlink -f $0\\``\n\
-                 exec $cwd/{}/venv/{}/python $cwd/{}/entry.py {} \"$@\"",
-                current_name, VENV_BIN, current_name, entry_arg
+                 current=$cwd/{}@`uname -n`\n\
+                 [ -e \"$current\" ] || current=$cwd/{}\n\
+                 exec $current/venv/{}/python $current/entry.py {} \"$@\"",
+                current_name, current_name, VENV_BIN, entry_arg
             )
         } else if cfg!(windows) {
             format!(
@@ -1492,122 +4001,165 @@ impl InstallationsData {
             ));
         };
 
-        // write
-        match fs::File::create(file_path) {
-            Ok(mut file) => {
-                if let Err(e) = file.write(contents.as_bytes()) {
-                    return Err(Error::new(
-                        e.kind(),
-                        format!("failed to write to shortcut file: {}", e),
-                    ));
-                }
-            }
-            Err(e) => {
+        if let Err(e) = Self::helper_write_file_atomically(file_path, contents.as_bytes()) {
+            return Err(Error::new(
+                e.kind(),
+                format!("failed to write shortcut script: {}", e),
+            ));
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// helper func
+    ///
+    /// write contents to a temporary file next to file_path, then rename it over file_path,
+    /// so that anyone reading file_path sees either old or new contents, never a partial file.
+    /// on unix the file is made executable, as it's only used for scripts
+    ///
+    fn helper_write_file_atomically(file_path: &Path, contents: &[u8]) -> Result<(), Error> {
+        let file_name = match file_path.file_name() {
+            Some(x) => x.to_string_lossy(),
+            None => {
                 return Err(Error::new(
-                    e.kind(),
-                    format!("failed to create shortcut script: {}", e),
+                    std::io::ErrorKind::InvalidInput,
+                    "given path has no file name",
                 ));
             }
         };
+        let temp_path =
+            file_path.with_file_name(format!(".{}.{}", file_name, helper_random_name()));
+
+        let result = fs::write(&temp_path, contents).and_then(|_| {
+            #[cfg(unix)]
+            {
+                // set unix permissions
+                let mut perms = fs::metadata(&temp_path)?.permissions();
+                perms.set_mode(perms.mode() | 0o111);
+                fs::set_permissions(&temp_path, perms)?;
+            }
+            fs::rename(&temp_path, file_path)
+        });
+        if result.is_err() && temp_path.exists() {
+            if let Err(e) = fs::remove_file(&temp_path) {
+                eprintln!("failed to remove temporary file {:?}: {}", temp_path, e);
+            }
+        }
 
-        #[cfg(unix)]
-        {
-            // set unix permissions
-            let mut perms = match fs::metadata(file_path) {
-                Ok(m) => m.permissions(),
-                Err(e) => {
-                    return Err(Error::new(
-                        e.kind(),
-                        format!("failed to set permissions on shortcut: {}", e),
-                    ));
-                }
-            };
-            perms.set_mode(perms.mode() | 0o111);
-            if let Err(e) = fs::set_permissions(file_path, perms) {
+        result
+    }
+
+    ///
+    /// helper func
+    ///
+    /// figure out what dir a script made by helper_make_script_link points to,
+    /// relative to the dir the script is in
+    ///
+    fn helper_get_script_link_target(file_path: &Path) -> Result<String, Error> {
+        let contents = match fs::read_to_string(file_path) {
+            Ok(x) => x,
+            Err(e) => {
                 return Err(Error::new(
                     e.kind(),
-                    format!("failed to set permissions on shortcut: {}", e),
+                    format!("failed to read launcher script: {}", e),
                 ));
             }
+        };
+        let target = if cfg!(windows) {
+            // first line is expected to be "@rem <target>"
+            contents
+                .lines()
+                .next()
+                .and_then(|x| x.strip_prefix("@rem "))
+                .map(|x| x.trim())
+        } else if let Some((_, rest)) = contents.split_once("|| current=$cwd/") {
+            // expecting "... || current=$cwd/<target>", the fallback for all hosts
+            rest.lines().next().map(|x| x.trim())
+        } else if let Some((_, rest)) = contents.split_once("\ncurrent=$cwd/") {
+            // expecting "current=$cwd/<target>", scripts without per host current
+            rest.lines().next().map(|x| x.trim())
+        } else {
+            // scripts made by older versions: "exec $cwd/<target>/venv/..."
+            contents
+                .split_once("exec $cwd/")
+                .and_then(|(_, x)| x.split_once("/venv/"))
+                .map(|(x, _)| x)
+        };
+        match target {
+            Some(x) if !x.is_empty() => Ok(x.to_owned()),
+            _ => Err(Error::new(
+                std::io::ErrorKind::InvalidData,
+                "unexpected launcher script contents",
+            )),
         }
-
-        Ok(())
     }
 }
 
 ///
-/// helper func
+/// path of the version "current" points to in given base path, if any
 ///
-/// save metadata file
+/// same as InstallationsData::from_dir figures it out, but without scanning everything
 ///
-fn helper_save_metadata(
-    info_file_path: &Path,
-    comment: &str,
-    commit_full: &str,
-    date: DateTime<Utc>,
-) -> Result<(), Error> {
-    let mut file = match fs::File::create(info_file_path) {
-        Ok(file) => BufWriter::new(file),
-        Err(e) => {
-            return Err(Error::new(
-                e.kind(),
-                format!("failed to create metadata file: {}", e),
-            ));
+pub(crate) fn current_version_path(base_path: &Path) -> Option<PathBuf> {
+    #[cfg(unix)]
+    {
+        if let Some(link_name) = host_current_link_name() {
+            if let Ok(link_target) = base_path.join(link_name).read_link() {
+                let path = base_path.join(link_target);
+                if path.exists() {
+                    return Some(path);
+                }
+            }
         }
-    };
+    }
+    pointed_version_path(base_path, "current", "lifeblood")
+}
+
+///
+/// path of the version that given "current" link points to on unix,
+/// or that given launcher points to on windows
+///
+fn pointed_version_path(
+    base_path: &Path,
+    current_name: &str,
+    launcher_name: &str,
+) -> Option<PathBuf> {
+    if cfg!(windows) {
+        let contents = fs::read_to_string(base_path.join(format!("{}.cmd", launcher_name))).ok()?;
+        // first line is expected to be "@rem <version path>"
+        let line = contents.lines().next()?;
+        Some(base_path.join(line.get(5..)?.trim()))
+    } else {
+        let link_target = base_path.join(current_name).read_link().ok()?;
+        Some(base_path.join(link_target))
+    }
+}
 
-    write!(file, "1\n")?;
-    write!(file, "{}\n", comment)?;
-    write!(file, "{}\n", commit_full)?;
-    write!(file, "{:?}", date)?;
+///
+/// name of this host's own "current" link, None if hostname cannot be figured out
+///
+fn host_current_link_name() -> Option<String> {
+    sysinfo::System::host_name()
+        .filter(|x| !x.is_empty() && !x.contains(['/', '\\']))
+        .map(|x| format!("{}{}", HOST_CURRENT_PREFIX, x))
+}
 
-    Ok(())
+///
+/// if given string is a full commit hash
+///
+fn is_valid_commit_hash(commit: &str) -> bool {
+    commit.len() == COMMIT_HASH_LEN && commit.chars().all(|x| x.is_ascii_hexdigit())
 }
 
 ///
 /// helper func
 ///
-/// read back previously saved metadata file
-/// returned values are: nice_name, source commit, date of creation
+/// random name for temporary files and dirs
 ///
-fn helper_read_metadata(info_file_path: &Path) -> Result<(String, String, DateTime<Utc>), Error> {
-    let mut file = match fs::File::open(info_file_path) {
-        Ok(file) => BufReader::new(file),
-        Err(e) => {
-            return Err(Error::new(
-                e.kind(),
-                format!("failed to create metadata file: {}", e),
-            ));
-        }
-    };
-
-    let mut buf = String::new();
-    file.read_line(&mut buf)?;
-    if buf.trim() != "1" {
-        return Err(Error::new(
-            std::io::ErrorKind::Other,
-            format!("unknown metadata format version: {}", buf),
-        ));
-    };
-
-    buf.clear();
-    file.read_line(&mut buf)?;
-    let comment = buf.trim().to_owned();
-
-    buf.clear();
-    file.read_line(&mut buf)?;
-    let commit_full = buf.trim().to_owned();
-
-    buf.clear();
-    file.read_line(&mut buf)?;
-    let date: DateTime<Utc> = if let Ok(x) = buf.trim().parse() {
-        x
-    } else {
-        return Err(Error::new(
-            std::io::ErrorKind::InvalidData,
-            "incorrect date format in metadata",
-        ));
-    };
-
-    Ok((comment, commit_full, date))
+pub(crate) fn helper_random_name() -> String {
+    let mut rng = thread_rng();
+    (0..16)
+        .map(|_| rng.sample(Alphanumeric) as char)
+        .collect::<String>()
 }
@@ -45,3 +45,15 @@ pub fn get_python_command() -> Option<PathBuf> {
 
     None
 }
+
+///
+/// helper func
+///
+/// python explicitly chosen with PYTHON_BIN env variable, if any
+///
+/// when it's not set - installation operations are expected to discover python themselves,
+/// see InstallationsData::set_auto_select_python
+///
+pub fn get_python_from_env() -> Option<PathBuf> {
+    std::env::var_os("PYTHON_BIN").map(PathBuf::from)
+}
@@ -1,15 +1,17 @@
 use crate::config_data_collection::ConfigDataCollection;
 use crate::info_dialog::InfoDialog;
-use crate::installation_helpers::get_python_command;
+use crate::installation_helpers::get_python_from_env;
 use crate::main_widget_config::MainWidgetConfig;
 use crate::theme::*;
 use crate::tray_manager::TrayManager;
 use crate::widgets::{Widget, WidgetCallbacks};
 use crate::wizard::{Wizard, WizardForToolsOnly};
-use crate::InstallationsData;
+use crate::{CancellationToken, DownloadSource, InstallEvent, InstallationsData, SourceRef};
 use fltk::button::CheckButton;
 use fltk::dialog;
-use fltk::misc::InputChoice;
+use fltk::menu::Choice;
+use fltk::misc::{InputChoice, Progress};
+use fltk::text::{TextBuffer, TextDisplay};
 use fltk::{
     app,
     button::Button,
@@ -24,9 +26,13 @@ use std::rc::Rc;
 use std::sync::MutexGuard;
 use std::{
     path::{Path, PathBuf},
-    sync::{Mutex, TryLockError},
+    sync::Mutex,
+};
+use std::{
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
 };
-use std::{sync::Arc, thread, time::Duration};
 #[cfg(windows)]
 use winconsole::window;
 
@@ -50,10 +56,25 @@ const DOWNLOAD_LABEL_ANIM: [&str; 12] = [
 pub struct InstallationWidget {
     install_data: Option<Arc<Mutex<InstallationsData>>>,
     installation_table: Table,
+    channel_selector: Choice,
+    channels: Vec<String>,
+    channel_filter: Option<String>,
+    shown_versions: Vec<usize>, // version indices in table row order
+    rows: Vec<VersionRow>,      // what is shown in table rows, so drawing does not lock data
+
     warning_label: Frame,
     main_flex: Flex,
 }
 
+///
+/// contents of one table row, taken when table is updated
+///
+#[derive(Clone)]
+struct VersionRow {
+    is_current: bool,
+    cells: Vec<String>,
+}
+
 fn lock_install_data(data: &Arc<Mutex<InstallationsData>>) -> MutexGuard<'_, InstallationsData> {
     if let Ok(x) = data.lock() {
         x
@@ -62,6 +83,84 @@ fn lock_install_data(data: &Arc<Mutex<InstallationsData>>) -> MutexGuard<'_, Ins
     }
 }
 
+///
+/// download source of opened installation, that may come from its own config,
+/// or the one of manager's config if none is opened
+///
+fn helper_download_source(
+    install_data: Option<&Arc<Mutex<InstallationsData>>>,
+    config: &RefCell<MainWidgetConfig>,
+) -> DownloadSource {
+    match install_data {
+        Some(x) => lock_install_data(x).download_source().clone(),
+        None => config.borrow().download_source().clone(),
+    }
+}
+
+///
+/// show all installation events received so far in progress bar and log
+///
+fn show_install_events(
+    receiver: &mpsc::Receiver<InstallEvent>,
+    progress_bar: &mut Progress,
+    log: &mut TextDisplay,
+) {
+    let mut log_lines = Vec::new();
+    while let Ok(event) = receiver.try_recv() {
+        match event {
+            InstallEvent::Phase(phase) => {
+                progress_bar.set_value(0.0);
+                progress_bar.set_label(&phase.to_string());
+                log_lines.push(format!("==> {}", phase));
+            }
+            InstallEvent::Download {
+                downloaded,
+                total: Some(total),
+            } if total > 0 => {
+                progress_bar.set_value(downloaded as f64 / total as f64);
+                progress_bar.set_label(&format!(
+                    "downloading {:.1}/{:.1} MB",
+                    downloaded as f64 / 1048576.0,
+                    total as f64 / 1048576.0
+                ));
+            }
+            InstallEvent::Download { downloaded, .. } => {
+                progress_bar.set_label(&format!(
+                    "downloading {:.1} MB",
+                    downloaded as f64 / 1048576.0
+                ));
+            }
+            InstallEvent::Unpack { done, total } => {
+                progress_bar.set_value(done as f64 / total.max(1) as f64);
+                progress_bar.set_label(&format!("unpacking {}/{} files", done, total));
+            }
+            InstallEvent::PipOutput(line) => {
+                log_lines.push(line);
+            }
+            InstallEvent::Finished(Ok(_)) => {
+                progress_bar.set_value(1.0);
+                progress_bar.set_label("done");
+            }
+            InstallEvent::Finished(Err(e)) => {
+                progress_bar.set_value(0.0);
+                progress_bar.set_label("failed");
+                log_lines.push(format!("==> failed: {}", e));
+            }
+        }
+    }
+
+    if log_lines.is_empty() {
+        return;
+    }
+    if let Some(mut buffer) = log.buffer() {
+        for line in log_lines {
+            buffer.append(&format!("{}\n", line));
+        }
+        log.set_insert_position(buffer.length());
+        log.show_insert_position();
+    }
+}
+
 impl InstallationWidget {
     pub fn change_install_dir(
         &mut self,
@@ -105,11 +204,77 @@ impl InstallationWidget {
     fn update_installation_table(&mut self) {
         if let Some(mutexed_data) = &self.install_data {
             let data = lock_install_data(&mutexed_data);
+
+            // channels may come and go with versions
+            self.channels = data.channels();
+            if let Some(ref channel) = self.channel_filter {
+                if !self.channels.contains(channel) {
+                    self.channel_filter = None;
+                }
+            }
+            self.channel_selector.clear();
+            self.channel_selector.add_choice("all channels");
+            for channel in self.channels.iter() {
+                // slash would make a submenu
+                self.channel_selector
+                    .add_choice(&channel.replace('/', "\\/"));
+            }
+            let selected = match self.channel_filter {
+                Some(ref channel) => self.channels.iter().position(|x| x == channel).unwrap() + 1,
+                None => 0,
+            };
+            self.channel_selector.set_value(selected as i32);
+
+            // newest on top
+            self.shown_versions = (0..data.version_count())
+                .rev()
+                .filter(|&i| match self.channel_filter {
+                    Some(ref channel) => {
+                        data.version(i).and_then(|x| x.channel()) == Some(channel.as_str())
+                    }
+                    None => true,
+                })
+                .collect();
+            self.rows = self
+                .shown_versions
+                .iter()
+                .filter_map(|&i| {
+                    let ver = data.version(i)?;
+                    let is_current = i == data.current_version_index();
+                    let channel = match ver.channel() {
+                        Some(channel) if data.channel_current_version_index(channel) == Some(i) => {
+                            format!("{} (current)", channel)
+                        }
+                        Some(channel) => channel.to_owned(),
+                        None => String::new(),
+                    };
+                    Some(VersionRow {
+                        is_current,
+                        cells: vec![
+                            if is_current { "current" } else { "" }.to_owned(),
+                            ver.nice_name().to_owned(),
+                            ver.date().format("%d-%m-%Y %H:%M:%S").to_string(),
+                            if ver.has_viewer() { "v" } else { " " }.to_owned(),
+                            if ver.is_pinned() { "pinned" } else { " " }.to_owned(),
+                            ver.source_commit().to_owned(),
+                            channel,
+                        ],
+                    })
+                })
+                .collect();
             self.installation_table
-                .set_rows(data.version_count() as i32);
+                .set_rows(self.shown_versions.len() as i32);
             self.installation_table.redraw();
         }
     }
+
+    ///
+    /// index of the version shown in given table row
+    ///
+    fn version_id_at_row(&self, row: i32) -> Option<usize> {
+        let row = usize::try_from(row).ok()?;
+        self.shown_versions.get(row).copied()
+    }
 }
 
 impl WidgetCallbacks for InstallationWidget {
@@ -132,7 +297,7 @@ impl WidgetCallbacks for InstallationWidget {
 }
 
 impl Widget for InstallationWidget {
-    fn initialize(_config: Rc<RefCell<MainWidgetConfig>>) -> (Arc<Mutex<Self>>, Flex) {
+    fn initialize(config: Rc<RefCell<MainWidgetConfig>>) -> (Arc<Mutex<Self>>, Flex) {
         let tab_header = Flex::default_fill().with_label("Installation\t").row();
         let mut flex = Flex::default_fill().column();
         flex.set_margin(8);
@@ -146,17 +311,35 @@ impl Widget for InstallationWidget {
         let mut installations_table = Table::default().with_size(200, 200);
         //tab_header.resizable(widget)
         installations_table.set_rows(0);
-        installations_table.set_cols(5);
+        installations_table.set_cols(7);
         installations_table.set_col_resize(true);
         installations_table.set_row_resize(true);
         installations_table.set_col_width(0, 64);
         installations_table.set_col_width(1, 250);
         installations_table.set_col_width(2, 150);
         installations_table.set_col_width(3, 16);
-        installations_table.set_col_width(4, 350);
+        installations_table.set_col_width(4, 50);
+        installations_table.set_col_width(5, 350);
+        installations_table.set_col_width(6, 150);
 
         installations_table.end();
 
+        // progress of long operations
+        let mut install_progress_row = Flex::default().row();
+        flex.fixed(&install_progress_row, ITEM_HEIGHT);
+        let mut install_progress_bar = Progress::default();
+        install_progress_bar.set_minimum(0.0);
+        install_progress_bar.set_maximum(1.0);
+        install_progress_bar.set_selection_color(enums::Color::Blue);
+        let mut cancel_install_btn = Button::default().with_label("cancel");
+        cancel_install_btn.set_tooltip("stop current operation and roll back what it did");
+        cancel_install_btn.deactivate();
+        install_progress_row.fixed(&cancel_install_btn, 100);
+        install_progress_row.end();
+        let mut install_log = TextDisplay::default();
+        install_log.set_buffer(TextBuffer::default());
+        flex.fixed(&install_log, 4 * ITEM_HEIGHT);
+
         // buttons
         let mut control_buttons_group_vertical = Flex::default().column();
         flex.fixed(&control_buttons_group_vertical, 2 * ITEM_HEIGHT);
@@ -165,6 +348,16 @@ impl Widget for InstallationWidget {
         control_buttons_group_vertical.fixed(&upper_control_row, ITEM_HEIGHT);
         let ignore_system_python_checkbox =
             CheckButton::default().with_label("ignore system python");
+        let mut channel_selector = Choice::default();
+        channel_selector.add_choice("all channels");
+        channel_selector.set_value(0);
+        channel_selector.set_tooltip("show only versions of this channel");
+        upper_control_row.fixed(&channel_selector, 150);
+        let mut make_channel_current_btn =
+            Button::default().with_label("make selected current in its channel");
+        make_channel_current_btn
+            .set_tooltip("each channel has own current version and lifeblood-<channel> launchers");
+        upper_control_row.fixed(&make_channel_current_btn, 270);
         let mut wizard_button = Button::default().with_label("Config Wizard");
         wizard_button.set_tooltip("The wizard that will create configuration and install/update DCC submission tools");
         let mut tools_only_wizard_button = Button::default().with_label("Tools Update");
@@ -179,9 +372,18 @@ impl Widget for InstallationWidget {
         let mut branch_selector = InputChoice::default();
         branch_selector.add(DEFAULT_BRANCH);
         branch_selector.set_value(DEFAULT_BRANCH);
+        branch_selector.set_tooltip("branch name, or tag:<name>, or commit:<hash>");
         Frame::default();
+        let mut pin_ver_btn = Button::default().with_label("(un)pin selected");
+        pin_ver_btn.set_tooltip("pinned versions are never removed or pruned");
+        let mut remove_ver_btn = Button::default().with_label("remove selected");
+        let mut repair_ver_btn = Button::default().with_label("repair selected");
+        repair_ver_btn.set_tooltip("reinstall venv packages and launchers of selected version");
         let mut rename_ver_btn = Button::default().with_label("rename selected");
         let mut make_current_btn = Button::default().with_label("make selected version current");
+        version_control_flex.fixed(&pin_ver_btn, 130);
+        version_control_flex.fixed(&remove_ver_btn, 130);
+        version_control_flex.fixed(&repair_ver_btn, 130);
         version_control_flex.fixed(&rename_ver_btn, 130);
         version_control_flex.fixed(&make_current_btn, 230);
         version_control_flex.end();
@@ -194,6 +396,11 @@ impl Widget for InstallationWidget {
         let widget = InstallationWidget {
             install_data: None,
             installation_table: installations_table,
+            channel_selector: channel_selector.clone(),
+            channels: Vec::new(),
+            channel_filter: None,
+            shown_versions: Vec::new(),
+            rows: Vec::new(),
             warning_label: path_warning_label,
             main_flex: flex,
         };
@@ -205,135 +412,93 @@ impl Widget for InstallationWidget {
         //
 
         // table draw callback
+        // rows are taken once per redraw, as widget may be busy
         let widget_to_cb = widget.clone();
+        let mut rows: Option<Vec<VersionRow>> = None;
         widget
             .lock()
             .unwrap()
             .installation_table
-            .draw_cell(move |t, ctx, row, col, x, y, w, h| {
-                let ver_id = (t.rows() - 1 - row) as usize;
-                match ctx {
-                    TableContext::Cell => {
-                        draw::push_clip(x, y, w, h);
-                        draw::draw_box(
-                            enums::FrameType::ThinDownBox,
-                            x,
-                            y,
-                            w,
-                            h,
-                            if t.is_selected(row, col) {
-                                CELL_BG_SEL_COLOR
-                            } else {
-                                match &widget_to_cb.try_lock() {
-                                    Ok(guard) => match guard.install_data {
-                                        Some(ref mutexed_data) => {
-                                            // TODO: OMG, we are doing 2 locks for EACH CELL !!
-                                            let data = lock_install_data(&mutexed_data);
-                                            if data.current_version_index() == ver_id {
-                                                CELL_BG_CUR_COLOR
-                                            } else {
-                                                CELL_BG_COLOR
-                                            }
-                                        }
-                                        _ => CELL_BG_COLOR,
-                                    },
-                                    _ => CELL_BG_COLOR,
-                                }
-                            },
-                        );
-                        draw::set_draw_color(CELL_FG_COLOR);
-                        match &widget_to_cb.try_lock() {
-                            Ok(guard) => {
-                                if let Some(ref mutexed_data) = guard.install_data {
-                                    let data = lock_install_data(&mutexed_data);
-                                    match data.version(ver_id) {
-                                        Some(ver) => match col {
-                                            0 => {
-                                                if ver_id == data.current_version_index() {
-                                                    draw::draw_text2(
-                                                        "current",
-                                                        x,
-                                                        y,
-                                                        w,
-                                                        h,
-                                                        enums::Align::Center,
-                                                    );
-                                                }
-                                            }
-                                            1 => draw::draw_text2(
-                                                ver.nice_name(),
-                                                x,
-                                                y,
-                                                w,
-                                                h,
-                                                enums::Align::Center,
-                                            ),
-                                            2 => draw::draw_text2(
-                                                &ver.date().format("%d-%m-%Y %H:%M:%S").to_string(),
-                                                x,
-                                                y,
-                                                w,
-                                                h,
-                                                enums::Align::Center,
-                                            ),
-                                            3 => draw::draw_text2(
-                                                if ver.has_viewer() { "v" } else { " " },
-                                                x,
-                                                y,
-                                                w,
-                                                h,
-                                                enums::Align::Center,
-                                            ),
-                                            4 => draw::draw_text2(
-                                                ver.source_commit(),
-                                                x,
-                                                y,
-                                                w,
-                                                h,
-                                                enums::Align::Center,
-                                            ),
-                                            _ => draw::draw_text2(
-                                                "<ERROR>",
-                                                x,
-                                                y,
-                                                w,
-                                                h,
-                                                enums::Align::Center,
-                                            ),
-                                        },
-                                        None => {
-                                            draw::draw_text2(
-                                                "<ERROR>",
-                                                x,
-                                                y,
-                                                w,
-                                                h,
-                                                enums::Align::Center,
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                            Err(TryLockError::WouldBlock) => {
-                                draw::draw_text2(
-                                    "<data update in progress>",
-                                    x,
-                                    y,
-                                    w,
-                                    h,
-                                    enums::Align::Center,
-                                );
-                            }
-                            _ => {
-                                draw::draw_text2("<ALL BROKEN!>", x, y, w, h, enums::Align::Center);
+            .draw_cell(move |t, ctx, row, col, x, y, w, h| match ctx {
+                TableContext::StartPage => {
+                    rows = match widget_to_cb.try_lock() {
+                        Ok(guard) => Some(guard.rows.clone()),
+                        Err(_) => None,
+                    };
+                }
+                TableContext::Cell => {
+                    let version_row = rows
+                        .as_ref()
+                        .map(|x| usize::try_from(row).ok().and_then(|i| x.get(i)));
+                    draw::push_clip(x, y, w, h);
+                    draw::draw_box(
+                        enums::FrameType::ThinDownBox,
+                        x,
+                        y,
+                        w,
+                        h,
+                        if t.is_selected(row, col) {
+                            CELL_BG_SEL_COLOR
+                        } else {
+                            match version_row {
+                                Some(Some(x)) if x.is_current => CELL_BG_CUR_COLOR,
+                                _ => CELL_BG_COLOR,
                             }
-                        }
-                        draw::pop_clip();
-                    }
-                    _ => (),
+                        },
+                    );
+                    draw::set_draw_color(CELL_FG_COLOR);
+                    let cell = version_row.map(|x| {
+                        x.and_then(|x| usize::try_from(col).ok().and_then(|i| x.cells.get(i)))
+                    });
+                    let text = match cell {
+                        Some(Some(x)) => x.as_str(),
+                        Some(None) => "<ERROR>",
+                        None => "<data update in progress>",
+                    };
+                    draw::draw_text2(text, x, y, w, h, enums::Align::Center);
+                    draw::pop_clip();
                 }
+                _ => (),
             });
 
+        // channel filter callback
+        let widget_to_cb = widget.clone();
+        channel_selector.set_callback(move |choice| {
+            let mut guard = widget_to_cb.lock().unwrap();
+            guard.channel_filter = match choice.value() {
+                x if x > 0 => guard.channels.get(x as usize - 1).cloned(),
+                _ => None,
+            };
+            guard.installation_table.unset_selection();
+            guard.update_installation_table();
+        });
+
+        // set channel current button callback
+        let widget_to_cb = widget.clone();
+        make_channel_current_btn.set_callback(move |btn| {
+            let mut guard = widget_to_cb.lock().unwrap();
+
+            let (row, _, _, _) = guard.installation_table.get_selection();
+            let ver_id = match guard.version_id_at_row(row) {
+                Some(x) => x,
+                None => return,
+            };
+            if let Some(ref mut mutexed_data) = guard.install_data {
+                let mut data = lock_install_data(&mutexed_data);
+                if let Err(e) = data.make_version_channel_current(ver_id) {
+                    eprintln!("failed to make version current in its channel! {}", e);
+                    let wind = btn.window().unwrap();
+                    InfoDialog::show(
+                        wind.x() + wind.w() / 2 - 100,
+                        wind.y() + wind.h() / 2 - 50,
+                        "error",
+                        &format!("failed to make version current in its channel! {}", e),
+                    );
+                }
+            }
+            guard.update_installation_table();
+        });
+
         // rename button callback
         let widget_to_cb = widget.clone();
         rename_ver_btn.set_callback(move |btn| {
@@ -343,7 +508,10 @@ impl Widget for InstallationWidget {
                 return;
             }
 
-            let ver_id = (guard.installation_table.rows() - 1 - row) as usize;
+            let ver_id = match guard.version_id_at_row(row) {
+                Some(x) => x,
+                None => return,
+            };
             let mut install_data = if let Some(data) = &mut guard.install_data {
                 lock_install_data(data)
             } else {
@@ -380,7 +548,185 @@ impl Widget for InstallationWidget {
             }
             drop(install_data);
 
-            guard.installation_table.redraw();
+            guard.update_installation_table();
+        });
+
+        // pin button callback
+        let widget_to_cb = widget.clone();
+        pin_ver_btn.set_callback(move |btn| {
+            let mut guard = widget_to_cb.lock().unwrap();
+            let (row, _, _, _) = guard.installation_table.get_selection();
+            if row < 0 {
+                return;
+            }
+
+            let ver_id = match guard.version_id_at_row(row) {
+                Some(x) => x,
+                None => return,
+            };
+            let mut install_data = if let Some(data) = &mut guard.install_data {
+                lock_install_data(data)
+            } else {
+                return;
+            };
+
+            let result = match install_data.version(ver_id) {
+                Some(ver) if ver.is_pinned() => install_data.unpin_version(ver_id),
+                Some(_) => install_data.pin_version(ver_id),
+                None => return,
+            };
+            if let Err(e) = result {
+                eprintln!("failed to (un)pin! {}", e);
+                let wind = btn.window().unwrap();
+                InfoDialog::show(
+                    wind.x() + wind.w() / 2 - 100,
+                    wind.y() + wind.h() / 2 - 50,
+                    "error",
+                    &format!("failed to (un)pin! {}", e),
+                );
+            }
+            drop(install_data);
+
+            guard.update_installation_table();
+        });
+
+        // remove button callback
+        let widget_to_cb = widget.clone();
+        remove_ver_btn.set_callback(move |btn| {
+            let mut guard = widget_to_cb.lock().unwrap();
+            let (row, _, _, _) = guard.installation_table.get_selection();
+            if row < 0 {
+                return;
+            }
+
+            let ver_id = match guard.version_id_at_row(row) {
+                Some(x) => x,
+                None => return,
+            };
+            let mut install_data = if let Some(data) = &mut guard.install_data {
+                lock_install_data(data)
+            } else {
+                return;
+            };
+            let ver_name = if let Some(v) = install_data.version(ver_id) {
+                v.nice_name().to_owned()
+            } else {
+                return;
+            };
+
+            let wind = btn.window().unwrap();
+            let popup_x = wind.x() + wind.w() / 2 - 100;
+            let popup_y = wind.y() + wind.h() / 2 - 50;
+
+            if let Some(1) = dialog::choice2(
+                popup_x,
+                popup_y,
+                &format!("remove version {} from disk?", ver_name),
+                "cancel",
+                "remove",
+                "",
+            ) {
+                if let Err(e) = install_data.remove_version(ver_id) {
+                    eprintln!("failed to remove! {}", e);
+                    InfoDialog::show(
+                        popup_x,
+                        popup_y,
+                        "error",
+                        &format!("failed to remove! {}", e),
+                    );
+                }
+            }
+            drop(install_data);
+
+            guard.update_installation_table();
+        });
+
+        // cancel button callback
+        // token of the operation in progress, if any
+        let install_cancel_token: Rc<RefCell<Option<CancellationToken>>> =
+            Rc::new(RefCell::new(None));
+        let cancel_token_to_cb = install_cancel_token.clone();
+        cancel_install_btn.set_callback(move |btn| {
+            if let Some(ref token) = *cancel_token_to_cb.borrow() {
+                println!("cancelling current operation");
+                token.cancel();
+                btn.deactivate();
+            }
+        });
+
+        // repair button callback
+        let widget_to_cb = widget.clone();
+        let mut progress_bar_to_cb = install_progress_bar.clone();
+        let mut log_to_cb = install_log.clone();
+        let cancel_token_to_cb = install_cancel_token.clone();
+        let mut cancel_btn_to_cb = cancel_install_btn.clone();
+        let mut controls_to_cb = control_buttons_group_vertical.clone();
+        repair_ver_btn.set_callback(move |btn| {
+            // worker only needs data, widget stays free for drawing
+            let (ver_id, install_data) = {
+                let guard = widget_to_cb.lock().unwrap();
+                let (row, _, _, _) = guard.installation_table.get_selection();
+                (guard.version_id_at_row(row), guard.install_data.clone())
+            };
+            let (ver_id, install_data) = match (ver_id, install_data) {
+                (Some(x), Some(y)) => (x, y),
+                _ => return,
+            };
+            let (event_sender, event_receiver) = mpsc::channel();
+            let cancel_token = CancellationToken::new();
+            *cancel_token_to_cb.borrow_mut() = Some(cancel_token.clone());
+            cancel_btn_to_cb.activate();
+            // nothing else can be done with data until it's finished
+            controls_to_cb.deactivate();
+
+            thread::scope(|scope| {
+                let handle = scope.spawn(|| {
+                    let mut data = lock_install_data(&install_data);
+                    data.set_progress_callback(Some(Arc::new(move |event: &InstallEvent| {
+                        event_sender.send(event.clone()).ok();
+                    })));
+                    data.set_cancellation_token(Some(cancel_token.clone()));
+                    let result = data.repair_version(ver_id);
+                    data.set_progress_callback(None);
+                    data.set_cancellation_token(None);
+                    result.map_err(|e| format!("failed to repair! {}", e))
+                });
+
+                let btn_text = btn.label();
+                let mut anim_frame = 0;
+                // poll and keep UI responsive
+                while !handle.is_finished() {
+                    btn.set_label(DOWNLOAD_LABEL_ANIM[anim_frame]);
+                    anim_frame = (anim_frame + 1) % DOWNLOAD_LABEL_ANIM.len();
+                    show_install_events(&event_receiver, &mut progress_bar_to_cb, &mut log_to_cb);
+                    app::check();
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                btn.set_label(&btn_text);
+                show_install_events(&event_receiver, &mut progress_bar_to_cb, &mut log_to_cb);
+                *cancel_token_to_cb.borrow_mut() = None;
+                cancel_btn_to_cb.deactivate();
+                controls_to_cb.activate();
+
+                match handle.join() {
+                    Ok(Err(err_msg)) => {
+                        eprintln!("{}", err_msg);
+                        let wind = btn.window().unwrap();
+                        InfoDialog::show(
+                            wind.x() + wind.w() / 2 - 300,
+                            wind.y() + wind.h() / 2 - 100,
+                            "error",
+                            &err_msg,
+                        );
+                    }
+                    Err(e) => {
+                        eprintln!("thead join failed! {:?}", e);
+                    }
+                    _ => (),
+                }
+            });
+
+            widget_to_cb.lock().unwrap().update_installation_table();
         });
 
         // set current button callback
@@ -392,7 +738,10 @@ impl Widget for InstallationWidget {
             if row < 0 {
                 return;
             }
-            let ver_id = (guard.installation_table.rows() - 1 - row) as usize;
+            let ver_id = match guard.version_id_at_row(row) {
+                Some(x) => x,
+                None => return,
+            };
             match guard.install_data {
                 Some(ref mut mutexed_data) => {
                     let mut data = lock_install_data(&mutexed_data);
@@ -402,15 +751,33 @@ impl Widget for InstallationWidget {
                 }
                 _ => (),
             }
-            guard.installation_table.redraw();
+            guard.update_installation_table();
         });
 
         // download freshhhh
         let widget_to_cb = widget.clone();
+        let config_to_cb = config.clone();
+        let mut progress_bar_to_cb = install_progress_bar.clone();
+        let mut log_to_cb = install_log.clone();
+        let cancel_token_to_cb = install_cancel_token.clone();
+        let mut cancel_btn_to_cb = cancel_install_btn.clone();
+        let mut controls_to_cb = control_buttons_group_vertical.clone();
         new_install_btn.set_callback(move |btn| {
-            let branch = match branch_selector.value() {
-                Some(x) => x,
-                None => DEFAULT_BRANCH.to_owned(),
+            let source_ref = match branch_selector.value() {
+                Some(x) => match x.trim().parse::<SourceRef>() {
+                    Ok(source_ref) => source_ref,
+                    Err(e) => {
+                        let wind = btn.window().unwrap();
+                        InfoDialog::show(
+                            wind.x() + (wind.w() / 2) as i32 - 300,
+                            wind.y() + (wind.h() / 2) as i32 - 100,
+                            "error",
+                            &format!("bad branch/ref: {}", e),
+                        );
+                        return;
+                    }
+                },
+                None => SourceRef::Branch(DEFAULT_BRANCH.to_owned()),
             };
             let ignore_system_python = ignore_system_python_checkbox.value();
             let mut installation_succeeded = true;
@@ -423,30 +790,49 @@ impl Widget for InstallationWidget {
                 window::activate(true);
             }
 
+            // worker only needs data, widget stays free for drawing
+            let install_data = widget_to_cb.lock().unwrap().install_data.clone();
+            let (event_sender, event_receiver) = mpsc::channel();
+            let cancel_token = CancellationToken::new();
+            *cancel_token_to_cb.borrow_mut() = Some(cancel_token.clone());
+            cancel_btn_to_cb.activate();
+            // nothing else can be done with data until it's finished
+            controls_to_cb.deactivate();
             thread::scope(|scope| {
                 let handle = scope.spawn(|| {
-                    let guard = &mut widget_to_cb.lock().unwrap();
-                    match guard.install_data {
-                        Some(ref mut mutexed_data) => {
+                    match install_data {
+                        Some(ref mutexed_data) => {
                             let mut data = lock_install_data(&mutexed_data);
                             // if checkbox is set - we don't try to locate python
+                            // otherwise if it's not given explicitly - the newest suitable
+                            // one is found during install
                             println!("initiating new version installation");
                             let path_to_python = if ignore_system_python {
                                 None
                             } else {
-                                get_python_command()
+                                get_python_from_env()
                             };
+                            data.set_auto_select_python(!ignore_system_python);
 
                             if let Some(ref path) = path_to_python {
                                 println!("using python: {:?}", path);
                             }
 
                             // download latest
-                            let new_ver = match data.download_new_version(
-                                &branch,
+                            data.set_progress_callback(Some(Arc::new(
+                                move |event: &InstallEvent| {
+                                    event_sender.send(event.clone()).ok();
+                                },
+                            )));
+                            data.set_cancellation_token(Some(cancel_token.clone()));
+                            let result = data.download_new_version(
+                                &source_ref,
                                 true,
                                 path_to_python.as_deref(),
-                            ) {
+                            );
+                            data.set_progress_callback(None);
+                            data.set_cancellation_token(None);
+                            let new_ver = match result {
                                 Ok(idx) => {
                                     // TODO: result process somehow
                                     idx
@@ -461,6 +847,16 @@ impl Widget for InstallationWidget {
                                 let err_msg = format!("failed to make new version current: {}", e);
                                 eprintln!("Warning: {}", err_msg);
                             }
+                            // and current of its channel
+                            let has_channel =
+                                data.version(new_ver).is_some_and(|x| x.channel().is_some());
+                            if has_channel {
+                                if let Err(e) = data.make_version_channel_current(new_ver) {
+                                    let err_msg =
+                                        format!("failed to update channel current: {}", e);
+                                    eprintln!("Warning: {}", err_msg);
+                                }
+                            }
                         }
                         _ => (),
                     }
@@ -473,11 +869,16 @@ impl Widget for InstallationWidget {
                 while !handle.is_finished() {
                     btn.set_label(DOWNLOAD_LABEL_ANIM[anim_frame]);
                     anim_frame = (anim_frame + 1) % DOWNLOAD_LABEL_ANIM.len();
+                    show_install_events(&event_receiver, &mut progress_bar_to_cb, &mut log_to_cb);
                     app::check();
                     // app::flush();
                     std::thread::sleep(Duration::from_millis(100));
                 }
                 btn.set_label(&btn_text);
+                show_install_events(&event_receiver, &mut progress_bar_to_cb, &mut log_to_cb);
+                *cancel_token_to_cb.borrow_mut() = None;
+                cancel_btn_to_cb.deactivate();
+                controls_to_cb.activate();
 
                 // join
                 match handle.join() {
@@ -510,19 +911,32 @@ impl Widget for InstallationWidget {
             if installation_succeeded {
                 let config_root = ConfigDataCollection::default_config_location();
                 if !config_root.exists() {
-                    let mut wizard = Wizard::new(config_root);
+                    let download_source =
+                        helper_download_source(install_data.as_ref(), &config_to_cb);
+                    let mut wizard = Wizard::new(config_root, download_source);
                     wizard.run();
                 }
             }
         });
 
         // wizard callback
-        wizard_button.set_callback(|_| {
-            let mut wizard = Wizard::new(ConfigDataCollection::default_config_location());
+        let widget_to_cb = widget.clone();
+        let config_to_cb = config.clone();
+        wizard_button.set_callback(move |_| {
+            let install_data = widget_to_cb.lock().unwrap().install_data.clone();
+            let download_source = helper_download_source(install_data.as_ref(), &config_to_cb);
+            let mut wizard = Wizard::new(
+                ConfigDataCollection::default_config_location(),
+                download_source,
+            );
             wizard.run();
         });
-        tools_only_wizard_button.set_callback(|_| {
-            let mut wizard = WizardForToolsOnly::new();
+        let widget_to_cb = widget.clone();
+        let config_to_cb = config.clone();
+        tools_only_wizard_button.set_callback(move |_| {
+            let install_data = widget_to_cb.lock().unwrap().install_data.clone();
+            let download_source = helper_download_source(install_data.as_ref(), &config_to_cb);
+            let mut wizard = WizardForToolsOnly::new(download_source);
             wizard.run();
         });
 
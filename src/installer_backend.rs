@@ -0,0 +1,376 @@
+use crate::cancellation_token::CancellationToken;
+use crate::install_progress::{InstallEvent, ProgressReporter};
+use std::ffi::OsString;
+use std::io::{BufRead, BufReader, Error, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+///
+/// what creates venvs and installs requirements into them
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallerKind {
+    /// uv if it's configured or found in PATH, pip otherwise
+    #[default]
+    Auto,
+    /// python -m venv and python -m pip
+    Pip,
+    /// uv venv and uv pip
+    Uv,
+}
+
+impl std::str::FromStr for InstallerKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(InstallerKind::Auto),
+            "pip" => Ok(InstallerKind::Pip),
+            "uv" => Ok(InstallerKind::Uv),
+            x => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown installer {:?}, expected auto, pip or uv", x),
+            )),
+        }
+    }
+}
+
+///
+/// which installer to use for venvs of new versions
+///
+#[derive(Debug, Clone, PartialEq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct InstallerSettings {
+    pub backend: InstallerKind,
+    /// uv binary to use, if not set - it's looked for in PATH
+    pub uv_path: Option<PathBuf>,
+    /// package index to use instead of pypi
+    pub index_url: Option<String>,
+    /// package indices to use in addition to the main one
+    pub extra_index_urls: Vec<String>,
+    /// hosts to trust even without valid https
+    pub trusted_hosts: Vec<String>,
+    /// dir with wheels to install from, when set - no index is used at all
+    pub wheelhouse: Option<PathBuf>,
+}
+
+///
+/// creates venv and installs requirements into it
+///
+/// all the rest of installation (like making lifeblood visible to venv python)
+/// does not depend on which installer is used
+///
+pub(crate) trait InstallerBackend {
+    fn name(&self) -> &'static str;
+
+    ///
+    /// create venv named "venv" inside dest_dir with given python
+    ///
+    fn create_venv(
+        &self,
+        dest_dir: &Path,
+        python: &Path,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<(), Error>;
+
+    ///
+    /// install given requirements file into venv of given venv python
+    ///
+    /// if constraints file is given - versions of packages listed in it are pinned to it
+    ///
+    fn install_requirements(
+        &self,
+        dest_dir: &Path,
+        venv_python: &Path,
+        requirements_path: &Path,
+        constraints_path: Option<&Path>,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<(), Error>;
+}
+
+struct PipInstaller {
+    index_args: Vec<OsString>,
+}
+
+struct UvInstaller {
+    uv: PathBuf,
+    index_args: Vec<OsString>,
+}
+
+impl InstallerSettings {
+    ///
+    /// installer to use with these settings
+    ///
+    /// fails if uv is asked for explicitly, but cannot be found
+    ///
+    pub(crate) fn installer(&self) -> Result<Box<dyn InstallerBackend>, Error> {
+        let uv = match &self.uv_path {
+            Some(x) => Some(x.to_owned()),
+            None => find_in_path("uv"),
+        };
+        match (self.backend, uv) {
+            (InstallerKind::Pip, _) | (InstallerKind::Auto, None) => Ok(Box::new(PipInstaller {
+                index_args: self.index_args("--trusted-host"),
+            })),
+            (InstallerKind::Uv, None) => Err(Error::new(
+                ErrorKind::NotFound,
+                "uv installer is chosen, but uv is not found in PATH, set uv_path to it",
+            )),
+            (_, Some(uv)) => {
+                if !uv.exists() {
+                    return Err(Error::new(
+                        ErrorKind::NotFound,
+                        format!("uv not found at {:?}", uv),
+                    ));
+                }
+                Ok(Box::new(UvInstaller {
+                    uv,
+                    index_args: self.index_args("--allow-insecure-host"),
+                }))
+            }
+        }
+    }
+
+    ///
+    /// pip install arguments telling where to get packages from
+    ///
+    pub(crate) fn pip_index_args(&self) -> Vec<OsString> {
+        self.index_args("--trusted-host")
+    }
+
+    fn index_args(&self, trusted_host_flag: &str) -> Vec<OsString> {
+        let mut args: Vec<OsString> = Vec::new();
+        if let Some(wheelhouse) = &self.wheelhouse {
+            args.push("--no-index".into());
+            args.push("--find-links".into());
+            args.push(wheelhouse.into());
+            return args;
+        }
+        if let Some(url) = &self.index_url {
+            args.push("--index-url".into());
+            args.push(url.into());
+        }
+        for url in self.extra_index_urls.iter() {
+            args.push("--extra-index-url".into());
+            args.push(url.into());
+        }
+        for host in self.trusted_hosts.iter() {
+            args.push(trusted_host_flag.into());
+            args.push(host.into());
+        }
+        args
+    }
+}
+
+impl InstallerBackend for PipInstaller {
+    fn name(&self) -> &'static str {
+        "pip"
+    }
+
+    fn create_venv(
+        &self,
+        dest_dir: &Path,
+        python: &Path,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<(), Error> {
+        let mut command = process::Command::new(python);
+        command
+            .current_dir(dest_dir)
+            .arg("-m")
+            .arg("venv")
+            .arg("venv");
+        run_checked(&mut command, "python", progress, cancel)
+    }
+
+    fn install_requirements(
+        &self,
+        dest_dir: &Path,
+        venv_python: &Path,
+        requirements_path: &Path,
+        constraints_path: Option<&Path>,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<(), Error> {
+        let mut command = process::Command::new(venv_python);
+        command
+            .current_dir(dest_dir)
+            .arg("-m")
+            .arg("pip")
+            .arg("install")
+            .args(&self.index_args);
+        if let Some(constraints_path) = constraints_path {
+            command.arg("-c").arg(constraints_path);
+        }
+        command.arg("-r").arg(requirements_path);
+        run_checked(&mut command, "pip", progress, cancel)
+    }
+}
+
+impl InstallerBackend for UvInstaller {
+    fn name(&self) -> &'static str {
+        "uv"
+    }
+
+    fn create_venv(
+        &self,
+        dest_dir: &Path,
+        python: &Path,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<(), Error> {
+        // seeded with pip, as venv is also checked and repaired with plain pip later
+        let mut command = process::Command::new(&self.uv);
+        command
+            .current_dir(dest_dir)
+            .arg("venv")
+            .arg("--seed")
+            .args(&self.index_args)
+            .arg("--python")
+            .arg(python)
+            .arg("venv");
+        run_checked(&mut command, "uv", progress, cancel)
+    }
+
+    fn install_requirements(
+        &self,
+        dest_dir: &Path,
+        venv_python: &Path,
+        requirements_path: &Path,
+        constraints_path: Option<&Path>,
+        progress: &ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<(), Error> {
+        let mut command = process::Command::new(&self.uv);
+        command
+            .current_dir(dest_dir)
+            .arg("pip")
+            .arg("install")
+            .args(&self.index_args)
+            .arg("--python")
+            .arg(venv_python);
+        if let Some(constraints_path) = constraints_path {
+            command.arg("-c").arg(constraints_path);
+        }
+        command.arg("-r").arg(requirements_path);
+        run_checked(&mut command, "uv", progress, cancel)
+    }
+}
+
+///
+/// run command reporting its output, fail if it does not exit with success
+///
+pub(crate) fn run_checked(
+    command: &mut process::Command,
+    what: &str,
+    progress: &ProgressReporter,
+    cancel: &CancellationToken,
+) -> Result<(), Error> {
+    let exit_status = match run_reporting_output(command, progress, cancel) {
+        Ok(status) => status,
+        Err(e) => {
+            return Err(Error::new(
+                e.kind(),
+                format!("error running {}: {}", what, e),
+            ));
+        }
+    };
+    if !exit_status.success() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!(
+                "{} process exited with status: {}",
+                what,
+                exit_status.code().unwrap_or(-1)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+///
+/// run given command and wait for it to finish
+///
+/// if anyone listens to progress - output is captured and reported line by line,
+/// otherwise it goes straight to our stdout and stderr
+///
+/// if cancellation is requested while waiting - the process is killed
+///
+pub(crate) fn run_reporting_output(
+    command: &mut process::Command,
+    progress: &ProgressReporter,
+    cancel: &CancellationToken,
+) -> Result<process::ExitStatus, Error> {
+    cancel.check()?;
+    if progress.is_enabled() {
+        command
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::piped());
+    }
+    let mut child = command.spawn()?;
+
+    let mut output_threads = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        output_threads.push(report_output_lines(stdout, progress.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        output_threads.push(report_output_lines(stderr, progress.clone()));
+    }
+
+    let result = loop {
+        // checked first, as process may be already dead of the same ctrl+c that cancelled us
+        if cancel.is_cancelled() {
+            println!("cancelled, killing process {}", child.id());
+            if let Err(e) = child.kill() {
+                eprintln!("failed to kill process: {}", e);
+            }
+            if let Err(e) = child.wait() {
+                eprintln!("failed to wait for killed process: {}", e);
+            }
+            break Err(Error::new(ErrorKind::Interrupted, "process was cancelled"));
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) => {}
+            Err(e) => break Err(e),
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    };
+
+    for output_thread in output_threads {
+        if output_thread.join().is_err() {
+            eprintln!("failed to read process output");
+        }
+    }
+    result
+}
+
+fn report_output_lines<R: Read + Send + 'static>(
+    output: R,
+    progress: ProgressReporter,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        for line in BufReader::new(output).lines().map_while(Result::ok) {
+            progress.report(InstallEvent::PipOutput(line));
+        }
+    })
+}
+
+///
+/// find binary with given name in PATH
+///
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let file_name = if cfg!(windows) {
+        format!("{}.exe", name)
+    } else {
+        name.to_owned()
+    };
+    let path_env = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_env)
+        .map(|dir| dir.join(&file_name))
+        .find(|x| x.is_file())
+}
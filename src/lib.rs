@@ -1,6 +1,21 @@
 pub const BUILD_INFO: &'static str = if let Some(x) = option_env!("LM_BUILD_VERSION") { x } else {"UNKNOWN BUILD"};
 
+mod base_dir_lock;
+mod cancellation_token;
+mod download_cache;
+mod download_source;
+mod install_error;
+mod install_progress;
 mod installation_data;
+mod installer_backend;
+mod main_widget_config;
+mod package_metadata;
+mod python_discovery;
+mod python_version;
+mod retention_policy;
+mod version_bundle;
+mod version_metadata;
+mod version_verification;
 mod running_process_data;
 mod launch_data;
 mod proc;
@@ -8,7 +23,20 @@ mod wizard;
 pub mod config_data;
 pub mod installation_helpers;
 pub mod config_data_collection;
-pub use installation_data::{InstallationsData, InstalledVersion};
+pub use base_dir_lock::BaseDirLockSettings;
+pub use cancellation_token::CancellationToken;
+pub use download_cache::DownloadCacheSettings;
+pub use download_source::DownloadSource;
+pub use install_error::InstallError;
+pub use install_progress::{InstallEvent, InstallPhase, ProgressCallback};
+pub use installation_data::{InstallationsData, InstalledVersion, SourceRef, UpdateInfo};
+pub use installer_backend::{InstallerKind, InstallerSettings};
+pub use main_widget_config::MainWidgetConfig;
+pub use package_metadata::PackageMetadata;
+pub use python_discovery::{PythonCandidate, PythonDiscovery, PythonSource};
+pub use python_version::{PythonVersion, VersionSpecifiers};
+pub use retention_policy::RetentionPolicy;
+pub use version_verification::{VerificationCheck, VerificationReport};
 pub use launch_data::LaunchControlData;
 pub use running_process_data::LaunchedProcess;
 
@@ -26,8 +54,6 @@ mod installation_widget;
 #[cfg(feature = "ui")]
 mod launch_widget;
 #[cfg(feature = "ui")]
-mod main_widget_config;
-#[cfg(feature = "ui")]
 mod envres_config_widget;
 #[cfg(feature = "ui")]
 pub mod theme;
@@ -36,8 +62,6 @@ pub use installation_widget::InstallationWidget;
 #[cfg(feature = "ui")]
 pub use launch_widget::LaunchWidget;
 #[cfg(feature = "ui")]
-pub use main_widget_config::MainWidgetConfig;
-#[cfg(feature = "ui")]
 pub use envres_config_widget::StandardEnvResolverConfigWidget;
 #[cfg(feature = "ui")]
 pub use widgets::{Widget, WidgetCallbacks};
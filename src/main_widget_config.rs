@@ -1,4 +1,6 @@
-use serde;
+use crate::{
+    BaseDirLockSettings, DownloadCacheSettings, DownloadSource, InstallerSettings, RetentionPolicy,
+};
 use std::collections::HashMap;
 use std::fs;
 use std::{
@@ -22,6 +24,16 @@ struct ConfigData {
     launch_ids_to_autostart: HashSet<String>,
     extra_fields: HashMap<String, ExtraFieldValue>,
     // additional launch data parameters may be stored here, or even the whole launch data?
+    #[serde(default)]
+    download_source: DownloadSource,
+    #[serde(default)]
+    download_cache: DownloadCacheSettings,
+    #[serde(default)]
+    retention_policy: RetentionPolicy,
+    #[serde(default)]
+    base_dir_lock: BaseDirLockSettings,
+    #[serde(default)]
+    installer: InstallerSettings,
 }
 
 pub struct MainWidgetConfig {
@@ -51,6 +63,11 @@ impl MainWidgetConfig {
                 base_install_dir: installation_dir.to_path_buf(),
                 launch_ids_to_autostart: HashSet::new(),
                 extra_fields: HashMap::new(),
+                download_source: DownloadSource::default(),
+                download_cache: DownloadCacheSettings::default(),
+                retention_policy: RetentionPolicy::default(),
+                base_dir_lock: BaseDirLockSettings::default(),
+                installer: InstallerSettings::default(),
             },
         }
     }
@@ -77,6 +94,19 @@ impl MainWidgetConfig {
         Ok(config)
     }
 
+    ///
+    /// load config kept in the installation dir itself, None if there is none
+    ///
+    /// install settings there belong to that installation,
+    /// so they are used instead of the ones of whichever manager opened it
+    ///
+    pub fn new_from_install_dir(install_dir: &Path) -> Result<Option<MainWidgetConfig>, Error> {
+        if !install_dir.join("lifeblood-manager.config").exists() {
+            return Ok(None);
+        }
+        Self::new_from_file(install_dir, false, false).map(Some)
+    }
+
     pub fn launch_ids_to_autostart(&self) -> &HashSet<String> {
         &self.config_data.launch_ids_to_autostart
     }
@@ -103,6 +133,46 @@ impl MainWidgetConfig {
         self.config_data.base_install_dir = path.to_path_buf();
     }
 
+    pub fn download_source(&self) -> &DownloadSource {
+        &self.config_data.download_source
+    }
+
+    pub fn set_download_source(&mut self, download_source: DownloadSource) {
+        self.config_data.download_source = download_source;
+    }
+
+    pub fn download_cache_settings(&self) -> &DownloadCacheSettings {
+        &self.config_data.download_cache
+    }
+
+    pub fn set_download_cache_settings(&mut self, settings: DownloadCacheSettings) {
+        self.config_data.download_cache = settings;
+    }
+
+    pub fn retention_policy(&self) -> &RetentionPolicy {
+        &self.config_data.retention_policy
+    }
+
+    pub fn set_retention_policy(&mut self, policy: RetentionPolicy) {
+        self.config_data.retention_policy = policy;
+    }
+
+    pub fn base_dir_lock_settings(&self) -> &BaseDirLockSettings {
+        &self.config_data.base_dir_lock
+    }
+
+    pub fn set_base_dir_lock_settings(&mut self, settings: BaseDirLockSettings) {
+        self.config_data.base_dir_lock = settings;
+    }
+
+    pub fn installer_settings(&self) -> &InstallerSettings {
+        &self.config_data.installer
+    }
+
+    pub fn set_installer_settings(&mut self, settings: InstallerSettings) {
+        self.config_data.installer = settings;
+    }
+
     pub fn write_to_file(&self) -> Result<(), Error> {
         if let Some(ref config_file_path) = self.config_path {
             let config_string = match toml::to_string_pretty(&self.config_data) {
@@ -0,0 +1,200 @@
+use crate::python_version::VersionSpecifiers;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+///
+/// what we need to know about a python package (like pkg_lifeblood) to install it
+///
+/// it's read from package's setup.cfg, and whatever is missing there
+/// is taken from [project] table of pyproject.toml
+///
+#[derive(Debug, Clone, Default)]
+pub struct PackageMetadata {
+    /// requirement lines, without requirements on lifeblood packages themselves
+    pub requirements: Vec<String>,
+    /// python versions package declares to work with, any if not declared
+    pub python_requires: VersionSpecifiers,
+}
+
+impl PackageMetadata {
+    ///
+    /// read metadata of the package in given dir
+    ///
+    pub fn from_package_dir(package_dir: &Path) -> Result<PackageMetadata, Error> {
+        let setupcfg_path = package_dir.join("setup.cfg");
+        let pyproject_path = package_dir.join("pyproject.toml");
+        if !setupcfg_path.exists() && !pyproject_path.exists() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("neither setup.cfg nor pyproject.toml found in {:?}", package_dir),
+            ));
+        }
+
+        let mut requirements = None;
+        let mut python_requires = None;
+        if setupcfg_path.exists() {
+            let ini = IniFile::parse(&read_file(&setupcfg_path)?);
+            if let Some(value) = ini.get("options", "install_requires") {
+                requirements = Some(
+                    value
+                        .lines()
+                        .map(|x| x.trim())
+                        .filter(|x| !x.is_empty() && !x.starts_with('#'))
+                        .map(|x| x.to_owned())
+                        .collect::<Vec<String>>(),
+                );
+            }
+            if let Some(value) = ini.get("options", "python_requires") {
+                python_requires = Some(value.to_owned());
+            }
+        }
+
+        if (requirements.is_none() || python_requires.is_none()) && pyproject_path.exists() {
+            let pyproject = match read_file(&pyproject_path)?.parse::<toml::Table>() {
+                Ok(x) => x,
+                Err(e) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("failed to parse pyproject.toml: {}", e),
+                    ))
+                }
+            };
+            if let Some(project) = pyproject.get("project").and_then(|x| x.as_table()) {
+                if requirements.is_none() {
+                    if let Some(deps) = project.get("dependencies").and_then(|x| x.as_array()) {
+                        requirements = Some(
+                            deps.iter()
+                                .filter_map(|x| x.as_str())
+                                .map(|x| x.trim().to_owned())
+                                .collect(),
+                        );
+                    }
+                }
+                if python_requires.is_none() {
+                    python_requires = project
+                        .get("requires-python")
+                        .and_then(|x| x.as_str())
+                        .map(|x| x.to_owned());
+                }
+            }
+        }
+
+        let python_requires = match python_requires {
+            Some(x) => match x.parse() {
+                Ok(x) => x,
+                Err(e) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("failed to parse python_requires: {}", e),
+                    ))
+                }
+            },
+            None => VersionSpecifiers::default(),
+        };
+
+        Ok(PackageMetadata {
+            requirements: requirements
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|x| !is_lifeblood_requirement(x)) // skip deps on self
+                .collect(),
+            python_requires,
+        })
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, Error> {
+    match fs::read_to_string(path) {
+        Ok(x) => Ok(x),
+        Err(e) => Err(Error::new(
+            e.kind(),
+            format!("failed to read {:?}: {}", path, e),
+        )),
+    }
+}
+
+///
+/// if requirement is on lifeblood or lifeblood_viewer, those are not installed from pypi
+///
+fn is_lifeblood_requirement(requirement: &str) -> bool {
+    let name_len = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(requirement.len());
+    requirement[..name_len]
+        .to_lowercase()
+        .starts_with("lifeblood")
+}
+
+///
+/// ini file the way python's configparser reads it, which is what setuptools uses for setup.cfg
+///
+/// values may continue on following indented lines, those are joined with new lines
+///
+struct IniFile {
+    sections: HashMap<String, HashMap<String, String>>,
+}
+
+impl IniFile {
+    fn parse(text: &str) -> IniFile {
+        let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut section: Option<String> = None;
+        let mut key: Option<String> = None;
+
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('#') || trimmed.starts_with(';') {
+                continue;
+            }
+            let is_continuation = line.starts_with([' ', '\t']);
+            if trimmed.is_empty() || is_continuation {
+                // empty lines may be inside multiline value too, they are trimmed in the end
+                if let (Some(section), Some(key)) = (&section, &key) {
+                    if let Some(value) = sections.get_mut(section).and_then(|x| x.get_mut(key)) {
+                        value.push('\n');
+                        value.push_str(trimmed);
+                    }
+                }
+                continue;
+            }
+
+            if let Some(name) = trimmed.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+                let name = name.trim().to_owned();
+                sections.entry(name.clone()).or_default();
+                section = Some(name);
+                key = None;
+                continue;
+            }
+
+            let section = match &section {
+                Some(x) => x,
+                None => continue, // no section header yet, configparser would fail, we just skip
+            };
+            let delimiter = trimmed.find(['=', ':']);
+            let (name, value) = match delimiter {
+                Some(pos) => (&trimmed[..pos], &trimmed[pos + 1..]),
+                None => (trimmed, ""),
+            };
+            let name = name.trim().to_lowercase();
+            if let Some(values) = sections.get_mut(section) {
+                values.insert(name.clone(), value.trim().to_owned());
+            }
+            key = Some(name);
+        }
+
+        for values in sections.values_mut() {
+            for value in values.values_mut() {
+                *value = value.trim().to_owned();
+            }
+        }
+        IniFile { sections }
+    }
+
+    fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .get(section)
+            .and_then(|x| x.get(key))
+            .map(|x| x.as_str())
+    }
+}
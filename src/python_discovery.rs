@@ -0,0 +1,371 @@
+use crate::python_version::{PythonVersion, VersionSpecifiers};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::process;
+
+///
+/// where a python interpreter candidate was found
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PythonSource {
+    /// PYTHON_BIN env variable
+    Env,
+    /// one of dirs in PATH
+    Path,
+    /// pyenv versions dir
+    Pyenv,
+    /// conda base installation or one of its envs
+    Conda,
+    /// one of common install locations, like /usr/local/bin
+    CommonPrefix,
+}
+
+///
+/// a python interpreter found on this machine
+///
+#[derive(Debug, Clone)]
+pub struct PythonCandidate {
+    path: PathBuf,
+    source: PythonSource,
+    /// version string like "3.12.4", or why it could not be found out
+    version: Result<String, String>,
+    /// if it's the same interpreter as some other candidate found earlier
+    same_as: Option<PathBuf>,
+}
+
+///
+/// python interpreters found on this machine,
+/// to choose one that suits the version being installed
+///
+#[derive(Debug, Clone, Default)]
+pub struct PythonDiscovery {
+    candidates: Vec<PythonCandidate>,
+}
+
+impl PythonCandidate {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn source(&self) -> PythonSource {
+        self.source
+    }
+
+    ///
+    /// version string as reported by the interpreter, None if it does not run
+    ///
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref().ok()
+    }
+
+    ///
+    /// why this candidate cannot be used for a version requiring given pythons,
+    /// None if it can be
+    ///
+    pub fn rejection_reason(&self, requires: &VersionSpecifiers) -> Option<String> {
+        if let Some(other) = &self.same_as {
+            return Some(format!("same interpreter as {:?}", other));
+        }
+        let version = match &self.version {
+            Ok(x) => x,
+            Err(e) => return Some(format!("does not run: {}", e)),
+        };
+        match PythonVersion::interpreter_release(version) {
+            Some(release) if requires.contains(&release) => None,
+            Some(_) => Some(format!("version {} does not satisfy {}", version, requires)),
+            None => Some(format!("cannot understand version {:?}", version)),
+        }
+    }
+}
+
+impl PythonDiscovery {
+    ///
+    /// look for pythons in PYTHON_BIN, PATH, pyenv, conda and common locations,
+    /// and run each one found to get its version
+    ///
+    pub fn run() -> PythonDiscovery {
+        let mut candidates: Vec<PythonCandidate> = Vec::new();
+        let mut seen_paths: HashSet<PathBuf> = HashSet::new();
+        let mut seen_real_paths: HashMap<PathBuf, PathBuf> = HashMap::new();
+
+        for (path, source) in Self::candidate_paths() {
+            if !seen_paths.insert(path.clone()) {
+                continue; // literally the same path, like /usr/bin being in PATH twice
+            }
+            // python3 is usually a link to python3.X, no need to run it twice
+            let real_path = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            let same_as = seen_real_paths.get(&real_path).cloned();
+            if same_as.is_none() {
+                seen_real_paths.insert(real_path, path.clone());
+            }
+            let version = match &same_as {
+                Some(other) => candidates
+                    .iter()
+                    .find(|x| x.path == *other)
+                    .map(|x| x.version.clone())
+                    .unwrap_or_else(|| Err("unknown".to_owned())),
+                None => get_python_version(&path).map_err(|e| e.to_string()),
+            };
+            candidates.push(PythonCandidate {
+                path,
+                source,
+                version,
+                same_as,
+            });
+        }
+
+        PythonDiscovery { candidates }
+    }
+
+    pub fn candidates(&self) -> &[PythonCandidate] {
+        &self.candidates
+    }
+
+    ///
+    /// newest python satisfying given requirements,
+    /// of several of the same version - the one found first
+    ///
+    pub fn choose(&self, requires: &VersionSpecifiers) -> Option<&PythonCandidate> {
+        let mut best: Option<(&PythonCandidate, PythonVersion)> = None;
+        for candidate in self.candidates.iter() {
+            if candidate.rejection_reason(requires).is_some() {
+                continue;
+            }
+            let release = match candidate.version().and_then(PythonVersion::interpreter_release) {
+                Some(x) => x,
+                None => continue,
+            };
+            match &best {
+                Some((_, best_release)) if *best_release >= release => (),
+                _ => best = Some((candidate, release)),
+            }
+        }
+        best.map(|(x, _)| x)
+    }
+
+    ///
+    /// all places to check, in order of preference
+    ///
+    fn candidate_paths() -> Vec<(PathBuf, PythonSource)> {
+        let mut paths = Vec::new();
+        if let Some(x) = std::env::var_os("PYTHON_BIN") {
+            paths.push((PathBuf::from(x), PythonSource::Env));
+        }
+
+        if let Some(path_env) = std::env::var_os("PATH") {
+            for dir in std::env::split_paths(&path_env) {
+                paths.extend(pythons_in_dir(&dir).into_iter().map(|x| (x, PythonSource::Path)));
+            }
+        }
+
+        let home = home::home_dir();
+
+        let pyenv_root = match (std::env::var_os("PYENV_ROOT"), &home) {
+            (Some(x), _) => Some(PathBuf::from(x)),
+            (None, Some(home)) => Some(home.join(".pyenv")),
+            _ => None,
+        };
+        if let Some(pyenv_root) = pyenv_root {
+            let versions_dir = if cfg!(windows) {
+                pyenv_root.join("pyenv-win").join("versions")
+            } else {
+                pyenv_root.join("versions")
+            };
+            for dir in subdirs(&versions_dir, "") {
+                paths.extend(
+                    pythons_in_dir(&bin_dir(&dir))
+                        .into_iter()
+                        .map(|x| (x, PythonSource::Pyenv)),
+                );
+            }
+        }
+
+        let mut conda_roots = Vec::new();
+        if let Some(x) = std::env::var_os("CONDA_PREFIX") {
+            conda_roots.push(PathBuf::from(x));
+        }
+        if let Some(home) = &home {
+            for name in ["miniconda3", "anaconda3", "miniforge3", "mambaforge", "micromamba"] {
+                conda_roots.push(home.join(name));
+            }
+        }
+        for root in conda_roots {
+            let envs = subdirs(&root.join("envs"), "");
+            for dir in std::iter::once(root).chain(envs) {
+                paths.extend(
+                    pythons_in_dir(&bin_dir(&dir))
+                        .into_iter()
+                        .map(|x| (x, PythonSource::Conda)),
+                );
+            }
+        }
+
+        for dir in common_prefix_dirs() {
+            paths.extend(
+                pythons_in_dir(&dir)
+                    .into_iter()
+                    .map(|x| (x, PythonSource::CommonPrefix)),
+            );
+        }
+
+        paths
+    }
+}
+
+///
+/// version of given python as "X.Y.Z", as it reports it with --version
+///
+pub(crate) fn get_python_version(python_bin: &Path) -> Result<String, Error> {
+    let output = process::Command::new(python_bin).arg("--version").output()?;
+    if !output.status.success() {
+        return Err(Error::new(
+            std::io::ErrorKind::Other,
+            format!("python exited with {}", output.status),
+        ));
+    }
+    // output is expected to be of a form "Python X.Y.Z", python2 prints it to stderr
+    let ver_str = match String::from_utf8_lossy(&output.stdout) {
+        x if x.trim().is_empty() => String::from_utf8_lossy(&output.stderr),
+        x => x,
+    };
+    match ver_str.trim().split_once(' ') {
+        Some((_, ver)) => Ok(ver.to_owned()),
+        None => Err(Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("unexpected python output {:?}", ver_str.trim()),
+        )),
+    }
+}
+
+///
+/// dir with python binary inside python installation or venv
+///
+fn bin_dir(prefix: &Path) -> PathBuf {
+    if cfg!(windows) {
+        prefix.to_path_buf()
+    } else {
+        prefix.join("bin")
+    }
+}
+
+///
+/// sorted subdirs of given dir, which names start with given prefix
+///
+fn subdirs(dir: &Path, name_prefix: &str) -> Vec<PathBuf> {
+    let mut dirs = match fs::read_dir(dir) {
+        Ok(x) => x
+            .filter_map(|x| x.ok())
+            .filter(|x| x.file_name().to_string_lossy().starts_with(name_prefix))
+            .map(|x| x.path())
+            .filter(|x| x.is_dir())
+            .collect::<Vec<PathBuf>>(),
+        Err(_) => Vec::new(),
+    };
+    dirs.sort();
+    dirs
+}
+
+#[cfg(unix)]
+fn common_prefix_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = [
+        "/usr/local/bin",
+        "/usr/bin",
+        "/opt/homebrew/bin",
+        "/opt/local/bin",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect();
+    for prefix in subdirs(Path::new("/opt"), "python") {
+        dirs.push(prefix.join("bin"));
+    }
+    for prefix in subdirs(Path::new("/Library/Frameworks/Python.framework/Versions"), "") {
+        dirs.push(prefix.join("bin"));
+    }
+    dirs
+}
+
+#[cfg(windows)]
+fn common_prefix_dirs() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(x) = std::env::var_os("LOCALAPPDATA") {
+        roots.push(PathBuf::from(x).join("Programs").join("Python"));
+    }
+    if let Some(x) = std::env::var_os("ProgramFiles") {
+        roots.push(PathBuf::from(x));
+    }
+    roots.push(PathBuf::from("C:\\"));
+    roots
+        .iter()
+        .flat_map(|root| subdirs(root, "Python"))
+        .collect()
+}
+
+///
+/// python, python3 and python3.X binaries in given dir
+///
+fn pythons_in_dir(dir: &Path) -> Vec<PathBuf> {
+    let mut pythons = match fs::read_dir(dir) {
+        Ok(x) => x
+            .filter_map(|x| x.ok())
+            .filter(|x| is_python_name(&x.file_name().to_string_lossy()))
+            .map(|x| x.path())
+            .filter(|x| is_executable(x))
+            .collect::<Vec<PathBuf>>(),
+        Err(_) => Vec::new(),
+    };
+    pythons.sort();
+    pythons
+}
+
+fn is_python_name(name: &str) -> bool {
+    let name = if cfg!(windows) {
+        match name.to_lowercase().strip_suffix(".exe") {
+            Some(x) => x.to_owned(),
+            None => return false,
+        }
+    } else {
+        name.to_owned()
+    };
+    match name.strip_prefix("python") {
+        Some("") | Some("3") => true,
+        Some(x) => match x.strip_prefix("3.") {
+            Some(minor) => !minor.is_empty() && minor.chars().all(|c| c.is_ascii_digit()),
+            None => false,
+        },
+        None => false,
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    match fs::metadata(path) {
+        Ok(x) => x.is_file() && x.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(windows)]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+impl fmt::Display for PythonSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PythonSource::Env => "PYTHON_BIN",
+                PythonSource::Path => "PATH",
+                PythonSource::Pyenv => "pyenv",
+                PythonSource::Conda => "conda",
+                PythonSource::CommonPrefix => "common location",
+            }
+        )
+    }
+}
@@ -0,0 +1,476 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::str::FromStr;
+
+///
+/// a version as python packaging understands it (PEP 440),
+/// like 3.12, 3.13.0rc1, 1!2.0.post1 or 2.0.dev3
+///
+/// local version label (the +something part) is dropped
+///
+#[derive(Debug, Clone)]
+pub struct PythonVersion {
+    epoch: u64,
+    release: Vec<u64>,
+    pre: Option<(PreRelease, u64)>,
+    post: Option<u64>,
+    dev: Option<u64>,
+}
+
+/// pre release, post release and dev release parts, made comparable
+type SuffixKey = ((u8, Option<(PreRelease, u64)>), Option<u64>, (u8, u64));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PreRelease {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+///
+/// comparison operator of a single version specifier
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Compatible,
+    Equal,
+    NotEqual,
+    LessEqual,
+    GreaterEqual,
+    Less,
+    Greater,
+    Arbitrary,
+}
+
+#[derive(Debug, Clone)]
+struct VersionSpecifier {
+    operator: Operator,
+    version: PythonVersion,
+    /// for == and != with .* at the end
+    wildcard: bool,
+    /// as it was written, needed for ===
+    text: String,
+}
+
+///
+/// comma separated set of PEP 440 version specifiers, like ">=3.8,<3.13"
+///
+/// a version matches if it matches every specifier, so empty set matches anything
+///
+#[derive(Debug, Clone, Default)]
+pub struct VersionSpecifiers {
+    specifiers: Vec<VersionSpecifier>,
+}
+
+impl PythonVersion {
+    pub fn new(release: &[u64]) -> PythonVersion {
+        PythonVersion {
+            epoch: 0,
+            release: release.to_vec(),
+            pre: None,
+            post: None,
+            dev: None,
+        }
+    }
+
+    ///
+    /// X.Y.Z release of a python interpreter from its version string, like "3.13.0rc1",
+    /// pip also checks python_requires against just that
+    ///
+    pub(crate) fn interpreter_release(version: &str) -> Option<PythonVersion> {
+        let release = version
+            .trim()
+            .split('.')
+            .take(3)
+            .map(|s| {
+                let digits_len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+                s[..digits_len].parse::<u64>().ok()
+            })
+            .collect::<Option<Vec<u64>>>()?;
+        if release.len() < 2 {
+            return None;
+        }
+        Some(PythonVersion::new(&release))
+    }
+
+    pub fn release(&self) -> &[u64] {
+        &self.release
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    ///
+    /// release segment, padded with zeros to given length
+    ///
+    fn padded_release(&self, len: usize) -> impl Iterator<Item = u64> + '_ {
+        self.release
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(0))
+            .take(len.max(self.release.len()))
+    }
+
+    fn release_starts_with(&self, prefix: &[u64]) -> bool {
+        self.padded_release(prefix.len())
+            .take(prefix.len())
+            .eq(prefix.iter().copied())
+    }
+
+    fn release_cmp(&self, other: &PythonVersion) -> Ordering {
+        let len = self.release.len().max(other.release.len());
+        self.padded_release(len).cmp(other.padded_release(len))
+    }
+
+    ///
+    /// sort key of the part after release segment, as python's packaging does it:
+    /// X.devN < XaN < XbN < XrcN < X < X.postN
+    ///
+    fn suffix_key(&self) -> SuffixKey {
+        let pre_key = match (self.pre, self.post, self.dev) {
+            (None, None, Some(_)) => (0, None),
+            (None, _, _) => (2, None),
+            (pre, _, _) => (1, pre),
+        };
+        let dev_key = match self.dev {
+            Some(x) => (0, x),
+            None => (1, 0),
+        };
+        (pre_key, self.post, dev_key)
+    }
+}
+
+impl FromStr for PythonVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_version = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{:?} is not a valid version", s),
+            )
+        };
+        let text = s.trim().to_lowercase();
+        let text = text.strip_prefix('v').unwrap_or(&text);
+        let text = match text.split_once('+') {
+            Some((public, _local)) => public,
+            None => text,
+        };
+
+        let (epoch, text) = match text.split_once('!') {
+            Some((epoch, rest)) => (epoch.parse::<u64>().map_err(|_| bad_version())?, rest),
+            None => (0, text),
+        };
+
+        let mut parser = SuffixParser { rest: text };
+        let mut release = vec![parser.number().ok_or_else(bad_version)?];
+        while let Some(rest) = parser.rest.strip_prefix('.') {
+            let mut next = SuffixParser { rest };
+            match next.number() {
+                Some(x) => {
+                    release.push(x);
+                    parser = next;
+                }
+                None => break,
+            }
+        }
+
+        let mut pre = None;
+        let mut post = None;
+        let mut dev = None;
+        if let Some(kind) = parser.word(&[
+            ("alpha", PreRelease::Alpha),
+            ("a", PreRelease::Alpha),
+            ("beta", PreRelease::Beta),
+            ("b", PreRelease::Beta),
+            ("preview", PreRelease::Rc),
+            ("pre", PreRelease::Rc),
+            ("rc", PreRelease::Rc),
+            ("c", PreRelease::Rc),
+        ]) {
+            pre = Some((kind, parser.separated_number().unwrap_or(0)));
+        }
+        if parser.word(&[("post", ()), ("rev", ()), ("r", ())]).is_some() {
+            post = Some(parser.separated_number().unwrap_or(0));
+        } else if let Some(rest) = parser.rest.strip_prefix('-') {
+            // implicit post release, like 1.0-1
+            let mut next = SuffixParser { rest };
+            if let Some(x) = next.number() {
+                post = Some(x);
+                parser = next;
+            }
+        }
+        if parser.word(&[("dev", ())]).is_some() {
+            dev = Some(parser.separated_number().unwrap_or(0));
+        }
+
+        if !parser.rest.is_empty() {
+            return Err(bad_version());
+        }
+        Ok(PythonVersion {
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+        })
+    }
+}
+
+///
+/// helper to eat version string piece by piece
+///
+struct SuffixParser<'a> {
+    rest: &'a str,
+}
+
+impl SuffixParser<'_> {
+    fn number(&mut self) -> Option<u64> {
+        let len = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let number = self.rest[..len].parse().ok()?;
+        self.rest = &self.rest[len..];
+        Some(number)
+    }
+
+    ///
+    /// number with optional separator in front of it
+    ///
+    fn separated_number(&mut self) -> Option<u64> {
+        let mut next = SuffixParser {
+            rest: self.rest.trim_start_matches(['.', '-', '_']),
+        };
+        let number = next.number()?;
+        self.rest = next.rest;
+        Some(number)
+    }
+
+    ///
+    /// one of given words with optional separator in front of it
+    ///
+    fn word<T: Copy>(&mut self, words: &[(&str, T)]) -> Option<T> {
+        let rest = self.rest.trim_start_matches(['.', '-', '_']);
+        for (word, value) in words {
+            if let Some(rest) = rest.strip_prefix(word) {
+                self.rest = rest;
+                return Some(*value);
+            }
+        }
+        None
+    }
+}
+
+impl PartialEq for PythonVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PythonVersion {}
+
+impl PartialOrd for PythonVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PythonVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| self.release_cmp(other))
+            .then_with(|| self.suffix_key().cmp(&other.suffix_key()))
+    }
+}
+
+impl fmt::Display for PythonVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+        write!(
+            f,
+            "{}",
+            self.release
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join(".")
+        )?;
+        if let Some((kind, number)) = self.pre {
+            let kind = match kind {
+                PreRelease::Alpha => "a",
+                PreRelease::Beta => "b",
+                PreRelease::Rc => "rc",
+            };
+            write!(f, "{}{}", kind, number)?;
+        }
+        if let Some(number) = self.post {
+            write!(f, ".post{}", number)?;
+        }
+        if let Some(number) = self.dev {
+            write!(f, ".dev{}", number)?;
+        }
+        Ok(())
+    }
+}
+
+impl VersionSpecifier {
+    fn contains(&self, version: &PythonVersion) -> bool {
+        let spec = &self.version;
+        match self.operator {
+            Operator::Equal if self.wildcard => self.prefix_matches(version),
+            Operator::NotEqual if self.wildcard => !self.prefix_matches(version),
+            Operator::Equal => version == spec,
+            Operator::NotEqual => version != spec,
+            Operator::LessEqual => version <= spec,
+            Operator::GreaterEqual => version >= spec,
+            Operator::Less => {
+                // <3.13 does not mean 3.13.0rc1 is fine
+                version < spec
+                    && (spec.is_prerelease()
+                        || !version.is_prerelease()
+                        || version.epoch != spec.epoch
+                        || version.release_cmp(spec) != Ordering::Equal)
+            }
+            Operator::Greater => {
+                // >3.8 does not mean 3.8.post1 is fine
+                version > spec
+                    && (spec.post.is_some()
+                        || version.post.is_none()
+                        || version.epoch != spec.epoch
+                        || version.release_cmp(spec) != Ordering::Equal)
+            }
+            Operator::Compatible => {
+                // ~=3.8.1 is >=3.8.1,==3.8.*
+                version >= spec
+                    && version.epoch == spec.epoch
+                    && version.release_starts_with(&spec.release[..spec.release.len() - 1])
+            }
+            Operator::Arbitrary => version.to_string() == self.text,
+        }
+    }
+
+    ///
+    /// for ==X.Y.* - if version's release starts with X.Y
+    ///
+    fn prefix_matches(&self, version: &PythonVersion) -> bool {
+        version.epoch == self.version.epoch && version.release_starts_with(&self.version.release)
+    }
+}
+
+impl FromStr for VersionSpecifier {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        let bad_specifier = |reason: &str| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{:?} is not a valid version specifier: {}", text, reason),
+            )
+        };
+        let (operator, rest) = [
+            ("~=", Operator::Compatible),
+            ("===", Operator::Arbitrary),
+            ("==", Operator::Equal),
+            ("!=", Operator::NotEqual),
+            ("<=", Operator::LessEqual),
+            (">=", Operator::GreaterEqual),
+            ("<", Operator::Less),
+            (">", Operator::Greater),
+        ]
+        .into_iter()
+        .find_map(|(prefix, op)| text.strip_prefix(prefix).map(|rest| (op, rest.trim())))
+        .ok_or_else(|| bad_specifier("unknown operator"))?;
+
+        if operator == Operator::Arbitrary {
+            return Ok(VersionSpecifier {
+                operator,
+                version: PythonVersion::new(&[0]),
+                wildcard: false,
+                text: rest.to_owned(),
+            });
+        }
+
+        let (version_text, wildcard) = match rest.strip_suffix(".*") {
+            Some(x) if matches!(operator, Operator::Equal | Operator::NotEqual) => (x, true),
+            Some(_) => return Err(bad_specifier("only == and != can have .*")),
+            None => (rest, false),
+        };
+        let version: PythonVersion = version_text
+            .parse()
+            .map_err(|e: Error| bad_specifier(&e.to_string()))?;
+        if operator == Operator::Compatible && version.release.len() < 2 {
+            return Err(bad_specifier("~= needs at least two release numbers"));
+        }
+
+        Ok(VersionSpecifier {
+            operator,
+            version,
+            wildcard,
+            text: rest.to_owned(),
+        })
+    }
+}
+
+impl VersionSpecifiers {
+    pub fn contains(&self, version: &PythonVersion) -> bool {
+        self.specifiers.iter().all(|x| x.contains(version))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.specifiers.is_empty()
+    }
+}
+
+impl FromStr for VersionSpecifiers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(VersionSpecifiers {
+            specifiers: s
+                .split(',')
+                .filter(|x| !x.trim().is_empty())
+                .map(|x| x.parse())
+                .collect::<Result<Vec<VersionSpecifier>, Error>>()?,
+        })
+    }
+}
+
+impl fmt::Display for VersionSpecifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.specifiers.is_empty() {
+            return write!(f, "any");
+        }
+        write!(
+            f,
+            "{}",
+            self.specifiers
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+impl fmt::Display for VersionSpecifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = match self.operator {
+            Operator::Compatible => "~=",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::LessEqual => "<=",
+            Operator::GreaterEqual => ">=",
+            Operator::Less => "<",
+            Operator::Greater => ">",
+            Operator::Arbitrary => "===",
+        };
+        write!(f, "{}{}", operator, self.text)
+    }
+}
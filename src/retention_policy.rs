@@ -0,0 +1,24 @@
+///
+/// which installed versions to keep when old versions are pruned
+///
+/// a version is kept if any of the rules keeps it,
+/// current, previous current and pinned versions are always kept.
+/// if no rule is set - nothing is pruned
+///
+#[derive(Debug, Clone, PartialEq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// keep this many newest versions
+    pub keep_newest: Option<usize>,
+    /// keep versions with date not older than this many days
+    pub keep_days: Option<u32>,
+}
+
+impl RetentionPolicy {
+    ///
+    /// true if policy may prune anything at all
+    ///
+    pub fn is_enabled(&self) -> bool {
+        self.keep_newest.is_some() || self.keep_days.is_some()
+    }
+}
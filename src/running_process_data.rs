@@ -1,13 +1,33 @@
+use crate::installation_data::current_version_path;
 use crate::proc::{create_process, terminate_child};
 use std::io;
-use std::path::{Path, PathBuf};
+use std::path::{absolute, Path, PathBuf};
 use std::process::{Child, ExitStatus};
+use std::sync::Mutex;
 use std::thread::sleep;
 use std::time::Duration;
 
+///
+/// versions that launched processes are running from,
+/// one entry per process, so same version may be here several times
+///
+static VERSIONS_IN_USE: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+///
+/// true if any LaunchedProcess alive in this manager was started from given version path
+///
+pub(crate) fn is_version_in_use(version_path: &Path) -> bool {
+    VERSIONS_IN_USE
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|x| x == version_path)
+}
+
 pub struct LaunchedProcess {
     running_process: Child,
     original_installation_path: PathBuf,
+    version_path: Option<PathBuf>,
 }
 
 impl LaunchedProcess {
@@ -17,9 +37,15 @@ impl LaunchedProcess {
         args: &Vec<String>,
     ) -> io::Result<LaunchedProcess> {
         let process = create_process(program, args, install_data_path)?;
+        // remember what current was at launch, as it may be changed while process is running
+        let version_path = current_version_path(&absolute(install_data_path)?);
+        if let Some(ref path) = version_path {
+            VERSIONS_IN_USE.lock().unwrap().push(path.clone());
+        }
         Ok(LaunchedProcess {
             running_process: process,
             original_installation_path: PathBuf::from(install_data_path),
+            version_path,
         })
     }
 
@@ -39,6 +65,13 @@ impl LaunchedProcess {
         &self.original_installation_path
     }
 
+    ///
+    /// path of the version that was current when process was launched
+    ///
+    pub fn version_path(&self) -> Option<&Path> {
+        self.version_path.as_deref()
+    }
+
     pub fn pid(&self) -> u32 {
         self.running_process.id()
     }
@@ -73,5 +106,13 @@ impl Drop for LaunchedProcess {
             }
             println!("[INFO] managed process stopped.");
         }
+
+        // note: if process failed to be killed above - its version stays in use
+        if let Some(ref path) = self.version_path {
+            let mut versions_in_use = VERSIONS_IN_USE.lock().unwrap();
+            if let Some(i) = versions_in_use.iter().position(|x| x == path) {
+                versions_in_use.remove(i);
+            }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,144 @@
+use crate::cancellation_token::CancellationToken;
+use crate::install_progress::ProgressReporter;
+use crate::installer_backend::run_checked;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{Error, Write};
+use std::path::Path;
+use std::process;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+///
+/// dir inside the bundle with version's files, as they are in version's dir, but without venv
+///
+pub(crate) const VERSION_DIR_NAME: &str = "version";
+
+///
+/// dir inside the bundle with wheels of every package of version's venv
+///
+pub(crate) const WHEELS_DIR_NAME: &str = "wheels";
+
+///
+/// what is taken from version's dir into the bundle, venv is made anew on import
+///
+const VERSION_ENTRIES: [&str; 6] = [
+    "lifeblood",
+    "lifeblood_viewer",
+    "entry.py",
+    "meta.info",
+    "requirements.txt",
+    "requirements_viewer.txt",
+];
+
+///
+/// build wheels of every package listed in lock file into wheels_dir with given venv python
+///
+/// wheels are taken (or built from sources) from wherever index_args point to
+///
+pub(crate) fn collect_wheels(
+    venv_python: &Path,
+    lock_path: &Path,
+    wheels_dir: &Path,
+    index_args: &[OsString],
+    progress: &ProgressReporter,
+    cancel: &CancellationToken,
+) -> Result<(), Error> {
+    // lock lists everything already, so no need to resolve dependencies again
+    let mut command = process::Command::new(venv_python);
+    command
+        .arg("-m")
+        .arg("pip")
+        .arg("wheel")
+        .arg("--no-deps")
+        .args(index_args)
+        .arg("-w")
+        .arg(wheels_dir)
+        .arg("-r")
+        .arg(lock_path);
+    run_checked(&mut command, "pip", progress, cancel)
+}
+
+///
+/// write bundle archive to out_path
+///
+/// given lock file is written as version's lock file, as version may not have one
+///
+pub(crate) fn write_bundle(
+    out_path: &Path,
+    version_dir: &Path,
+    lock_path: &Path,
+    wheels_dir: &Path,
+) -> Result<(), Error> {
+    // written next to destination first, so that failed export does not leave a broken bundle
+    let part_path = out_path.with_file_name(format!(
+        "{}.part",
+        out_path
+            .file_name()
+            .map(|x| x.to_string_lossy())
+            .unwrap_or_default()
+    ));
+    let result = fs::File::create(&part_path).and_then(|file| {
+        let mut zip = ZipWriter::new(file);
+        for entry in VERSION_ENTRIES {
+            let path = version_dir.join(entry);
+            if path.exists() {
+                add_to_zip(&mut zip, &path, &format!("{}/{}", VERSION_DIR_NAME, entry))?;
+            }
+        }
+        add_to_zip(
+            &mut zip,
+            lock_path,
+            &format!("{}/requirements.lock", VERSION_DIR_NAME),
+        )?;
+        add_to_zip(&mut zip, wheels_dir, WHEELS_DIR_NAME)?;
+        zip.finish()?;
+        Ok(())
+    });
+    match result {
+        Ok(_) => fs::rename(&part_path, out_path),
+        Err(e) => {
+            if let Err(e) = fs::remove_file(&part_path) {
+                eprintln!("failed to remove partially written bundle: {}", e);
+            }
+            Err(Error::new(
+                e.kind(),
+                format!("failed to write bundle {:?}: {}", out_path, e),
+            ))
+        }
+    }
+}
+
+///
+/// add file or dir with all its contents to the zip under given name
+///
+fn add_to_zip<W: Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    path: &Path,
+    name: &str,
+) -> Result<(), Error> {
+    let mut options = FileOptions::default();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        options = options.unix_permissions(fs::metadata(path)?.permissions().mode() & 0o777);
+    }
+
+    if !path.is_dir() {
+        zip.start_file(name, options)?;
+        std::io::copy(&mut fs::File::open(path)?, zip)?;
+        return Ok(());
+    }
+
+    zip.add_directory(name, options)?;
+    let mut entries = fs::read_dir(path)?.collect::<Result<Vec<_>, Error>>()?;
+    entries.sort_by_key(|x| x.file_name());
+    for entry in entries {
+        let entry_name = entry.file_name().to_string_lossy().to_string();
+        if entry_name == "__pycache__" {
+            continue;
+        }
+        add_to_zip(zip, &entry.path(), &format!("{}/{}", name, entry_name))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use crate::SourceRef;
+use chrono::prelude::*;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+const METADATA_FORMAT_VERSION: u32 = 2;
+
+///
+/// everything known about how a version was installed,
+/// saved as meta.info inside version's dir
+///
+/// format v1 was a plain list of lines: "1", nice name, commit, date and optional source ref,
+/// format v2 is toml, it's the one written now, but v1 can still be read
+///
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct VersionMetadata {
+    pub nice_name: String,
+    pub commit: String,
+    pub date: DateTime<Utc>,
+    pub source_url: Option<String>,
+    pub source_ref: Option<SourceRef>,
+    pub channel: Option<String>,
+    pub python: Option<PathBuf>,
+    pub python_version: Option<String>,
+    /// python versions supported by the installed package, as it's not kept with the version
+    pub python_requires: Option<String>,
+    pub has_viewer: Option<bool>,
+    pub manager_build: Option<String>,
+    pub install_duration_secs: Option<f64>,
+    pub notes: String,
+}
+
+///
+/// how v2 is laid out in the file
+///
+#[derive(serde::Deserialize, serde::Serialize)]
+struct VersionMetadataV2 {
+    format_version: u32,
+    nice_name: String,
+    commit: String,
+    date: String,
+    source_url: Option<String>,
+    source_ref: Option<String>,
+    channel: Option<String>,
+    python: Option<PathBuf>,
+    python_version: Option<String>,
+    python_requires: Option<String>,
+    has_viewer: Option<bool>,
+    manager_build: Option<String>,
+    install_duration_secs: Option<f64>,
+    #[serde(default)]
+    notes: String,
+}
+
+impl VersionMetadata {
+    pub fn write_to_file(&self, info_file_path: &Path) -> Result<(), Error> {
+        let data = VersionMetadataV2 {
+            format_version: METADATA_FORMAT_VERSION,
+            nice_name: self.nice_name.clone(),
+            commit: self.commit.clone(),
+            date: self.date.to_rfc3339_opts(SecondsFormat::Secs, true),
+            source_url: self.source_url.clone(),
+            source_ref: self.source_ref.as_ref().map(|x| x.to_string()),
+            channel: self.channel.clone(),
+            python: self.python.clone(),
+            python_version: self.python_version.clone(),
+            python_requires: self.python_requires.clone(),
+            has_viewer: self.has_viewer,
+            manager_build: self.manager_build.clone(),
+            install_duration_secs: self.install_duration_secs,
+            notes: self.notes.clone(),
+        };
+        let text = match toml::to_string_pretty(&data) {
+            Ok(x) => x,
+            Err(e) => {
+                return Err(Error::new(ErrorKind::InvalidData, e));
+            }
+        };
+        if let Err(e) = fs::write(info_file_path, text) {
+            return Err(Error::new(
+                e.kind(),
+                format!("failed to write metadata file: {}", e),
+            ));
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// read metadata of either format version
+    ///
+    pub fn read_from_file(info_file_path: &Path) -> Result<VersionMetadata, Error> {
+        let text = match fs::read_to_string(info_file_path) {
+            Ok(x) => x,
+            Err(e) => {
+                return Err(Error::new(
+                    e.kind(),
+                    format!("failed to read metadata file: {}", e),
+                ));
+            }
+        };
+
+        if text.lines().next().map(|x| x.trim()) == Some("1") {
+            return Self::parse_v1(&text);
+        }
+        Self::parse_v2(&text)
+    }
+
+    fn parse_v2(text: &str) -> Result<VersionMetadata, Error> {
+        let data: VersionMetadataV2 = match toml::from_str(text) {
+            Ok(x) => x,
+            Err(e) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unknown metadata format: {}", e.message()),
+                ));
+            }
+        };
+        if data.format_version != METADATA_FORMAT_VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unknown metadata format version: {}", data.format_version),
+            ));
+        }
+        let date = match data.date.parse() {
+            Ok(x) => x,
+            Err(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "incorrect date format in metadata",
+                ));
+            }
+        };
+
+        Ok(VersionMetadata {
+            nice_name: data.nice_name,
+            commit: data.commit,
+            date,
+            source_url: data.source_url,
+            source_ref: data.source_ref.and_then(|x| helper_parse_source_ref(&x)),
+            channel: data.channel,
+            python: data.python,
+            python_version: data.python_version,
+            python_requires: data.python_requires,
+            has_viewer: data.has_viewer,
+            manager_build: data.manager_build,
+            install_duration_secs: data.install_duration_secs,
+            notes: data.notes,
+        })
+    }
+
+    fn parse_v1(text: &str) -> Result<VersionMetadata, Error> {
+        let mut lines = text.lines().skip(1).map(|x| x.trim());
+        let nice_name = lines.next().unwrap_or_default().to_owned();
+        let commit = lines.next().unwrap_or_default().to_owned();
+        let date = match lines.next().unwrap_or_default().parse() {
+            Ok(x) => x,
+            Err(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "incorrect date format in metadata",
+                ));
+            }
+        };
+        // older v1 files do not have source ref
+        let source_ref = match lines.next() {
+            Some(x) if !x.is_empty() => helper_parse_source_ref(x),
+            _ => None,
+        };
+
+        Ok(VersionMetadata {
+            nice_name,
+            commit,
+            date,
+            source_ref,
+            ..VersionMetadata::default()
+        })
+    }
+}
+
+fn helper_parse_source_ref(text: &str) -> Option<SourceRef> {
+    match text.parse() {
+        Ok(x) => Some(x),
+        Err(e) => {
+            eprintln!("ignoring malformed source ref in metadata: {}", e);
+            None
+        }
+    }
+}
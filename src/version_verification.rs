@@ -0,0 +1,81 @@
+use std::fmt;
+
+///
+/// result of a single check performed during version verification
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationCheck {
+    name: String,
+    passed: bool,
+    details: String,
+}
+
+///
+/// result of verifying health of an installed version
+///
+/// consists of a list of checks, version is considered healthy if all of them passed
+///
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VerificationReport {
+    checks: Vec<VerificationCheck>,
+}
+
+impl VerificationCheck {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn passed(&self) -> bool {
+        self.passed
+    }
+
+    ///
+    /// what went wrong, or any extra info, may be empty
+    ///
+    pub fn details(&self) -> &str {
+        &self.details
+    }
+}
+
+impl VerificationReport {
+    pub(crate) fn add_check(&mut self, name: &str, passed: bool, details: String) {
+        self.checks.push(VerificationCheck {
+            name: name.to_owned(),
+            passed,
+            details,
+        });
+    }
+
+    pub fn checks(&self) -> &[VerificationCheck] {
+        &self.checks
+    }
+
+    ///
+    /// check with given name, if it was performed
+    ///
+    pub fn check(&self, name: &str) -> Option<&VerificationCheck> {
+        self.checks.iter().find(|x| x.name == name)
+    }
+
+    ///
+    /// true if all performed checks passed
+    ///
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|x| x.passed)
+    }
+}
+
+impl fmt::Display for VerificationCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}",
+            if self.passed { " ok " } else { "FAIL" },
+            self.name
+        )?;
+        if !self.details.is_empty() {
+            write!(f, ": {}", self.details)?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use crate::info_dialog::InfoDialog;
+use crate::DownloadSource;
 use crate::wizard::wizard_data::{BlenderVersion, HoudiniVersion, RedshiftVersion};
 
 use super::houdini_utils::possible_default_user_pref_dirs;
@@ -15,6 +16,7 @@ use super::{activities, wizard_activity::ActivityResult, wizard_activity_runner:
 
 pub struct Wizard {
     config_root: PathBuf,
+    download_source: DownloadSource,
     data: WizardData,
     state: WizardState,
 }
@@ -33,10 +35,11 @@ enum WizardState {
 }
 
 impl Wizard {
-    pub fn new(config_root: PathBuf) -> Self {
+    pub fn new(config_root: PathBuf, download_source: DownloadSource) -> Self {
         Wizard {
             data: WizardData::new_from_config(&config_root).unwrap_or_else(|_| WizardData::new_with_reasonable_defaults()),
             config_root,
+            download_source,
             state: WizardState::Intro,
         }
     }
@@ -322,7 +325,10 @@ impl Wizard {
         }
 
         println!("executing wizardry...");
-        if let Err(e) = self.data.execute_all_wizardry(&self.config_root) {
+        if let Err(e) = self
+            .data
+            .execute_all_wizardry(&self.config_root, &self.download_source)
+        {
             eprintln!("error executing wizardry: {:?}", e);
             InfoDialog::show_in_center(
                 "failed to execute wizardry :(",
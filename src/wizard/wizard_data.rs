@@ -1,3 +1,4 @@
+use crate::DownloadSource;
 use std::path::{Path, PathBuf};
 
 pub struct HoudiniVersion {
@@ -31,14 +32,18 @@ pub struct WizardData {
 }
 
 pub trait WizardDataSerialization {
-    fn execute_all_wizardry(&self, config_root: &Path) -> Result<(), std::io::Error> {
+    fn execute_all_wizardry(
+        &self,
+        config_root: &Path,
+        download_source: &DownloadSource,
+    ) -> Result<(), std::io::Error> {
         self.write_configs(config_root)?;
-        self.install_tools()?;
+        self.install_tools(download_source)?;
         Ok(())
     }
 
     fn write_configs(&self, config_root: &Path) -> Result<(), std::io::Error>;
-    fn install_tools(&self) -> Result<(), std::io::Error>;
+    fn install_tools(&self, download_source: &DownloadSource) -> Result<(), std::io::Error>;
 }
 
 impl WizardData {
@@ -8,7 +8,8 @@ use super::{
     },
 };
 use crate::{config_data::ConfigWritingError, config_data_collection::ConfigDataCollection};
-use downloader::{Download, Downloader};
+use crate::DownloadSource;
+use downloader::Download;
 use std::{
     collections::HashMap,
     fs::{self, File},
@@ -244,7 +245,7 @@ impl WizardDataSerialization for WizardData {
         Ok(())
     }
 
-    fn install_tools(&self) -> Result<(), std::io::Error> {
+    fn install_tools(&self, download_source: &DownloadSource) -> Result<(), std::io::Error> {
         // download and unpack latest release of tools/plugins
         if self.houdini_plugins_installation_paths.len() > 0 {
             let temp_location = tempdir()?;
@@ -254,25 +255,15 @@ impl WizardDataSerialization for WizardData {
             fs::create_dir(&tools_location)?;
 
             //download
-            let mut downloader = match Downloader::builder()
-                .connect_timeout(Duration::from_secs(60))
-                .timeout(Duration::from_secs(600))
-                .retries(5)
-                .download_folder(&download_location)
-                .build()
-            {
-                Ok(x) => x,
-                Err(e) => {
-                    return Err(Error::new(
-                        io::ErrorKind::Other,
-                        format!("failed to create a downloader: {:?}", e),
-                    ));
-                }
-            };
-            println!("[INFO] downloading tools from github...");
-            if let Err(e) = downloader.download(&[Download::new(
-                "https://github.com/pedohorse/lifeblood/releases/latest/download/houdini.zip",
-            )]) {
+            let mut downloader = download_source.build_downloader(
+                &download_location,
+                Duration::from_secs(60),
+                Duration::from_secs(600),
+                5,
+            )?;
+            let tools_url = download_source.tools_url();
+            println!("[INFO] downloading tools from {}...", tools_url);
+            if let Err(e) = downloader.download(&[Download::new(&tools_url)]) {
                 return Err(Error::new(
                     io::ErrorKind::Other,
                     format!("failed to download houdini tools: {:?}", e),
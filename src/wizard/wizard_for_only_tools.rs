@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use crate::info_dialog::InfoDialog;
+use crate::DownloadSource;
 
 use super::houdini_utils::possible_default_user_pref_dirs;
 use super::wizard_data::WizardData;
@@ -8,6 +9,7 @@ use super::wizard_data_to_config::*;
 use super::{activities, wizard_activity::ActivityResult, wizard_activity_runner::ActivityRunner};
 
 pub struct WizardForToolsOnly {
+    download_source: DownloadSource,
     data: WizardData,
     state: WizardState,
 }
@@ -18,8 +20,9 @@ enum WizardState {
 }
 
 impl WizardForToolsOnly {
-    pub fn new() -> Self {
+    pub fn new(download_source: DownloadSource) -> Self {
         WizardForToolsOnly {
+            download_source,
             data: WizardData::new(),
             state: WizardState::HoudiniTools,
         }
@@ -90,7 +93,7 @@ impl WizardForToolsOnly {
         }
 
         println!("installing houdini tools...");
-        if let Err(e) = self.data.install_tools() {
+        if let Err(e) = self.data.install_tools(&self.download_source) {
             eprintln!("error installing tools: {:?}", e);
             InfoDialog::show_in_center(
                 "failed to install tools :(",
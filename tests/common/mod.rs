@@ -0,0 +1,84 @@
+// not every test uses every helper
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+///
+/// fresh copy of tests/data/u_struct3 to modify, at tests/data/u_struct3_<name>_act
+///
+/// copying does not preserve links, so "current" is made anew, pointing to hash2
+///
+#[cfg(unix)]
+pub fn copy_fixture(name: &str) -> PathBuf {
+    let expected = PathBuf::from("./tests/data/u_struct3");
+    let actual = PathBuf::from(format!("./tests/data/u_struct3_{}_act", name));
+
+    if actual.exists() {
+        std::fs::remove_dir_all(&actual).unwrap();
+    }
+    let mut options = fs_extra::dir::CopyOptions::new();
+
+    options.copy_inside = true;
+    fs_extra::dir::copy(&expected, &actual, &options).unwrap();
+    std::fs::remove_dir_all(actual.join("current")).unwrap();
+    std::os::unix::fs::symlink("hash2", actual.join("current")).unwrap();
+
+    actual
+}
+
+///
+/// serve given bytes to anyone who asks, like a very dumb `python -m http.server`
+/// returns port and list of received request heads
+///
+pub fn serve_file(data: Vec<u8>) -> (u16, Arc<Mutex<Vec<Vec<String>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let requests_to_thread = requests.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(x) => x,
+                Err(_) => continue,
+            };
+            let mut head = Vec::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                    break;
+                }
+                head.push(line.trim().to_owned());
+            }
+            requests_to_thread.lock().unwrap().push(head);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                data.len()
+            )
+            .unwrap();
+            stream.write_all(&data).unwrap();
+        }
+    });
+    (port, requests)
+}
+
+///
+/// tell that the rest of given test is skipped, as what it needs is not there,
+/// so that it is not mistaken for a passed one
+///
+/// written to stderr directly, as test harness would capture eprintln
+///
+pub fn skip(test_name: &str, reason: &str) {
+    writeln!(
+        std::io::stderr(),
+        "{}: rest of the test is SKIPPED, {}",
+        test_name,
+        reason
+    )
+    .ok();
+}
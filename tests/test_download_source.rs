@@ -0,0 +1,193 @@
+use lifeblood_manager::{DownloadSource, InstallationsData, SourceRef};
+use std::io::Write;
+use std::path::PathBuf;
+
+mod common;
+
+#[test]
+fn test_download_source_urls() {
+    let source = DownloadSource::default();
+    assert_eq!(
+        "https://github.com/pedohorse/lifeblood/archive/refs/heads/dev.zip",
+        source.archive_url(&SourceRef::Branch("dev".to_owned()))
+    );
+    assert_eq!(
+        "https://github.com/pedohorse/lifeblood/archive/refs/tags/v1.0.zip",
+        source.archive_url(&SourceRef::Tag("v1.0".to_owned()))
+    );
+    assert_eq!(
+        "https://github.com/pedohorse/lifeblood/archive/f7a01d7c9ccc.zip",
+        source.archive_url(&SourceRef::Commit("f7a01d7c9ccc".to_owned()))
+    );
+    assert_eq!(
+        "https://github.com/pedohorse/lifeblood/releases/latest/download/houdini.zip",
+        source.tools_url()
+    );
+    assert_eq!(
+        "https://api.github.com/repos/pedohorse/lifeblood/commits/dev",
+        source.commit_info_url(&SourceRef::Branch("dev".to_owned()))
+    );
+
+    let source = DownloadSource {
+        base_url_template: "http://mirror.local:8000/lb/{owner}-{repo}/".to_owned(),
+        owner: "studio".to_owned(),
+        repo: "lifeblood-fork".to_owned(),
+        commit_info_url_template: "http://mirror.local:8000/api/{owner}/{repo}/{ref}".to_owned(),
+        auth_header: None,
+    };
+    assert_eq!(
+        "http://mirror.local:8000/lb/studio-lifeblood-fork/archive/refs/heads/master.zip",
+        source.archive_url(&SourceRef::Branch("master".to_owned()))
+    );
+    assert_eq!(
+        "http://mirror.local:8000/api/studio/lifeblood-fork/master",
+        source.commit_info_url(&SourceRef::Branch("master".to_owned()))
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_download_from_mirror() {
+    let actual = common::copy_fixture("mirror");
+
+    // fake branch archive of already installed commit
+    let mut zip_data = Vec::new();
+    {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+        zip.set_comment("a7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8");
+        zip.start_file("lifeblood-dev/entry.py", zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(b"print('hello')\n").unwrap();
+        zip.finish().unwrap();
+    }
+    let (port, requests) = common::serve_file(zip_data);
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.set_download_source(DownloadSource {
+        base_url_template: format!("http://127.0.0.1:{}/{{owner}}/{{repo}}", port),
+        owner: "someone".to_owned(),
+        repo: "lifeblood".to_owned(),
+        // plain mirror, no api to ask for head commits
+        commit_info_url_template: "".to_owned(),
+        auth_header: Some("Authorization: Bearer sometoken".to_owned()),
+        ..DownloadSource::default()
+    });
+
+    // second time archive is in cache, but branch head cannot be looked up, so it's downloaded
+    for _ in 0..2 {
+        let idx = ins
+            .download_new_version(&SourceRef::Branch("dev".to_owned()), false, None)
+            .unwrap();
+        assert_eq!("hash1", ins.version(idx).unwrap().nice_name());
+        assert_eq!(3, ins.version_count());
+    }
+    assert!(ins.check_for_update("dev").is_err());
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(2, requests.len());
+    for request in requests.iter() {
+        assert_eq!(
+            "GET /someone/lifeblood/archive/refs/heads/dev.zip HTTP/1.1",
+            request[0]
+        );
+    }
+    assert!(requests[0]
+        .iter()
+        .any(|x| x.to_lowercase() == "authorization: bearer sometoken"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_download_tag_from_cache() {
+    let actual = common::copy_fixture("tagcache");
+
+    // fake tag archive of already installed commit
+    let mut zip_data = Vec::new();
+    {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+        zip.set_comment("e7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8");
+        zip.start_file("lifeblood-v1.0/entry.py", zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(b"print('hello')\n").unwrap();
+        zip.finish().unwrap();
+    }
+    let (port, requests) = common::serve_file(zip_data);
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.set_download_source(DownloadSource {
+        base_url_template: format!("http://127.0.0.1:{}/{{owner}}/{{repo}}", port),
+        ..DownloadSource::default()
+    });
+
+    for _ in 0..2 {
+        let idx = ins
+            .download_new_version(&SourceRef::Tag("v1.0".to_owned()), false, None)
+            .unwrap();
+        assert_eq!("hash3", ins.version(idx).unwrap().nice_name());
+    }
+    // second time tag is found in cache
+    assert_eq!(1, requests.lock().unwrap().len());
+
+    // branch may have moved, so it's found in cache by its head commit
+    let (info_port, info_requests) = common::serve_file(
+        concat!(
+            r#"{"sha": "e7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8", "#,
+            r#""commit": {"committer": {"date": "2024-05-17T10:20:30Z"}}}"#
+        )
+        .as_bytes()
+        .to_vec(),
+    );
+    ins.set_download_source(DownloadSource {
+        base_url_template: format!("http://127.0.0.1:{}/{{owner}}/{{repo}}", port),
+        commit_info_url_template: format!(
+            "http://127.0.0.1:{}/repos/{{owner}}/{{repo}}/commits/{{ref}}",
+            info_port
+        ),
+        ..DownloadSource::default()
+    });
+    ins.download_new_version(&SourceRef::Branch("dev".to_owned()), false, None)
+        .unwrap();
+    assert_eq!(1, requests.lock().unwrap().len());
+    assert_eq!(1, info_requests.lock().unwrap().len());
+    assert_eq!(3, ins.version_count());
+}
+
+#[test]
+fn test_check_for_update() {
+    for (commit, expected_index) in [
+        ("a7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8", None),
+        ("f7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8", Some(0)),
+    ] {
+        let (port, requests) = common::serve_file(
+            format!(
+                r#"{{"sha": "{}", "commit": {{"committer": {{"date": "2024-05-17T10:20:30Z"}}}}}}"#,
+                commit
+            )
+            .into_bytes(),
+        );
+        let mut ins = InstallationsData::from_dir(PathBuf::from("./tests/data/u_struct1")).unwrap();
+        ins.set_download_source(DownloadSource {
+            commit_info_url_template: format!(
+                "http://127.0.0.1:{}/repos/{{owner}}/{{repo}}/commits/{{ref}}",
+                port
+            ),
+            ..DownloadSource::default()
+        });
+
+        let info = ins.check_for_update("dev").unwrap();
+        assert_eq!(commit, info.latest_commit());
+        assert_eq!(
+            "2024-05-17 10:20:30",
+            info.latest_date().format("%Y-%m-%d %H:%M:%S").to_string()
+        );
+        assert_eq!(expected_index, info.installed_version_index());
+        assert_eq!(expected_index.is_none(), info.is_update_available());
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(1, requests.len());
+        assert_eq!(
+            "GET /repos/pedohorse/lifeblood/commits/dev HTTP/1.1",
+            requests[0][0]
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use std::path::absolute;
-use lifeblood_manager::InstallationsData;
+use lifeblood_manager::{InstallationsData, SourceRef};
+
+mod common;
 
 
 #[test]
@@ -157,4 +159,1436 @@ fn test_read_unix_struct3() {
     assert_eq!(HashSet::from_iter(["heeehaaawsh", "foofash1", "hash3"].iter().map(|x| {x.to_string()})), dirset);
 
 
-}
\ No newline at end of file
+}
+
+#[cfg(unix)]
+#[test]
+fn test_install_from_zip_already_installed() {
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    let actual = common::copy_fixture("zip");
+
+    // fake branch archive, the way github makes it
+    let zip_path = PathBuf::from("./tests/data/u_struct3_zip_act.zip");
+    {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.set_comment("b7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8");
+        zip.add_directory("lifeblood-dev/", zip::write::FileOptions::default())
+            .unwrap();
+        zip.start_file("lifeblood-dev/entry.py", zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(b"print('hello')\n").unwrap();
+        zip.finish().unwrap();
+    }
+
+    let mut ins =
+        if let Ok(x) = InstallationsData::from_dir(actual.clone()) {
+            x
+        } else {
+            panic!("structure was not parsed!");
+        };
+
+    // same commit is already installed - nothing should be installed
+    let idx = ins.install_from_zip(&zip_path, false, None).unwrap();
+    assert_eq!(0, idx);
+    assert_eq!("hash2", ins.version(idx).unwrap().nice_name());
+    assert_eq!(3, ins.version_count());
+    assert!(zip_path.exists(), "given archive must not be removed");
+
+    // archive without commit information cannot be installed
+    {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.start_file("lifeblood-dev/entry.py", zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(b"print('hello')\n").unwrap();
+        zip.finish().unwrap();
+    }
+    assert!(ins.install_from_zip(&zip_path, false, None).is_err());
+    assert_eq!(3, ins.version_count());
+
+    // nor can one with something else than a full commit hash
+    for comment in ["not a commit hash at all, just a comment", "b7a01d7c9ccc0093d1c9"] {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.set_comment(comment);
+        zip.start_file("lifeblood-dev/entry.py", zip::write::FileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+        assert!(ins.install_from_zip(&zip_path, false, None).is_err());
+    }
+    assert_eq!(3, ins.version_count());
+
+    std::fs::remove_file(&zip_path).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_download_cache_eviction() {
+    use lifeblood_manager::DownloadCacheSettings;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    let actual = common::copy_fixture("cache");
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.is_base_path_tainted());
+    ins.set_download_cache_settings(DownloadCacheSettings {
+        size_limit_mb: 0,
+        ..DownloadCacheSettings::default()
+    });
+
+    let zip_path = PathBuf::from("./tests/data/u_struct3_cache_act.zip");
+    for commit in [
+        "b7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8",
+        "a7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8",
+    ] {
+        {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+            zip.set_comment(commit);
+            zip.start_file("lifeblood-dev/entry.py", zip::write::FileOptions::default())
+                .unwrap();
+            zip.write_all(b"print('hello')\n").unwrap();
+            zip.finish().unwrap();
+        }
+        ins.install_from_zip(&zip_path, false, None).unwrap();
+        assert!(actual
+            .join(".download_cache")
+            .join(format!("{}.zip", commit))
+            .exists());
+    }
+    // size limit is too small to keep anything but the latest
+    assert!(!actual
+        .join(".download_cache")
+        .join("b7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8.zip")
+        .exists());
+
+    // cache dir is not garbage
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.is_base_path_tainted());
+    assert_eq!(3, ins.version_count());
+
+    // cache right in base path shares its lock, it is not waited for
+    ins.set_download_cache_settings(DownloadCacheSettings {
+        location: Some(actual.clone()),
+        ..DownloadCacheSettings::default()
+    });
+    let started = std::time::Instant::now();
+    ins.install_from_zip(&zip_path, false, None).unwrap();
+    assert!(started.elapsed() < std::time::Duration::from_secs(30));
+    assert!(actual
+        .join("a7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8.zip")
+        .exists());
+
+    std::fs::remove_file(&zip_path).unwrap();
+}
+
+
+#[test]
+fn test_source_ref_parse() {
+    for (text, expected) in [
+        ("dev", SourceRef::Branch("dev".to_owned())),
+        ("branch:master", SourceRef::Branch("master".to_owned())),
+        ("tag:v1.2.3", SourceRef::Tag("v1.2.3".to_owned())),
+        ("commit:f7a01d7c9ccc", SourceRef::Commit("f7a01d7c9ccc".to_owned())),
+    ] {
+        let parsed: SourceRef = text.parse().unwrap();
+        assert_eq!(expected, parsed);
+        assert_eq!(expected, parsed.to_string().parse::<SourceRef>().unwrap());
+    }
+    assert!("tag:".parse::<SourceRef>().is_err());
+    assert!("smth:foo".parse::<SourceRef>().is_err());
+    // commit prefixes must be long enough to be meaningful
+    assert!("commit:f7a01d".parse::<SourceRef>().is_err());
+    assert!("commit:zzzzzzzzzz".parse::<SourceRef>().is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_download_commit_already_installed() {
+    let actual = common::copy_fixture("commit");
+
+    let mut ins =
+        if let Ok(x) = InstallationsData::from_dir(actual.clone()) {
+            x
+        } else {
+            panic!("structure was not parsed!");
+        };
+    assert!(ins.version(0).unwrap().source_ref().is_none());
+
+    // exact commit that is already installed must not even be downloaded
+    let idx = ins
+        .download_new_version(&SourceRef::Commit("e7a01d7c9ccc".to_owned()), false, None)
+        .unwrap();
+    assert_eq!(2, idx);
+    assert_eq!("hash3", ins.version(idx).unwrap().nice_name());
+    assert_eq!(3, ins.version_count());
+
+    // prefix shared by several installed commits must not pick one of them
+    std::fs::write(
+        actual.join("hash2").join("meta.info"),
+        "1\ncommmmmmmm\ne7a01d7c00000093d1c911f0a8a310f5fe49ddf8\n2023-10-25T02:53:18Z\n",
+    )
+    .unwrap();
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(ins
+        .download_new_version(&SourceRef::Commit("e7a01d7c".to_owned()), false, None)
+        .is_err());
+    assert!(ins
+        .download_new_version(&SourceRef::Commit("e7a01d7c9c".to_owned()), false, None)
+        .is_ok());
+    assert_eq!(3, ins.version_count());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_remove_version() {
+    use lifeblood_manager::LaunchedProcess;
+
+    let actual = common::copy_fixture("remove");
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert_eq!(0, ins.current_version_index());
+    assert_eq!("hash2", ins.version(0).unwrap().nice_name());
+    assert_eq!("hash1", ins.version(1).unwrap().nice_name());
+    assert_eq!("hash3", ins.version(2).unwrap().nice_name());
+
+    // current cannot be removed
+    assert!(ins.remove_version(0).is_err());
+    assert!(actual.join("hash2").exists());
+    assert!(ins.remove_version(3).is_err());
+
+    // process running from hash2 keeps it from being removed, even when it's not current any more
+    let proc =
+        LaunchedProcess::new(ins.base_path(), "/bin/sleep", &vec!["10".to_owned()]).unwrap();
+    assert_eq!(ins.version(0).unwrap().path(), proc.version_path().unwrap());
+    ins.make_version_current(2).unwrap();
+    assert!(ins.remove_version(0).is_err());
+    assert_eq!(3, ins.version_count());
+    drop(proc);
+
+    ins.remove_version(0).unwrap();
+    assert!(!actual.join("hash2").exists());
+    assert_eq!(2, ins.version_count());
+    assert_eq!("hash1", ins.version(0).unwrap().nice_name());
+    assert_eq!("hash3", ins.version(1).unwrap().nice_name());
+    assert_eq!(1, ins.current_version_index());
+    assert_eq!("hash3", ins.current_version().unwrap().nice_name());
+
+    // removing version before current shifts current index
+    ins.remove_version(0).unwrap();
+    assert_eq!(1, ins.version_count());
+    assert_eq!(0, ins.current_version_index());
+    assert_eq!("hash3", ins.current_version().unwrap().nice_name());
+
+    let ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert_eq!(1, ins.version_count());
+    assert!(!ins.is_base_path_tainted());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_prune_versions() {
+    use lifeblood_manager::RetentionPolicy;
+
+    let actual = common::copy_fixture("prune");
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert_eq!(usize::MAX, ins.previous_version_index());
+    ins.make_version_current(1).unwrap();
+    assert_eq!(0, ins.previous_version_index());
+
+    // previous current is remembered
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.is_base_path_tainted());
+    assert_eq!(1, ins.current_version_index());
+    assert_eq!(0, ins.previous_version_index());
+
+    // no policy - nothing to prune
+    assert!(ins.versions_to_prune().is_empty());
+
+    for (policy, expected_to_prune) in [
+        (
+            RetentionPolicy {
+                keep_newest: Some(0),
+                keep_days: None,
+            },
+            vec![2],
+        ),
+        (
+            RetentionPolicy {
+                keep_newest: Some(1),
+                keep_days: None,
+            },
+            vec![],
+        ),
+        (
+            RetentionPolicy {
+                keep_newest: None,
+                keep_days: Some(1),
+            },
+            vec![2],
+        ),
+        (
+            RetentionPolicy {
+                keep_newest: None,
+                keep_days: Some(365000),
+            },
+            vec![],
+        ),
+    ] {
+        ins.set_retention_policy(policy);
+        assert_eq!(expected_to_prune, ins.versions_to_prune());
+    }
+
+    ins.set_retention_policy(RetentionPolicy {
+        keep_newest: Some(0),
+        keep_days: None,
+    });
+    assert_eq!(vec!["hash3"], ins.prune_versions().unwrap());
+    assert!(!actual.join("hash3").exists());
+    assert_eq!(2, ins.version_count());
+    assert_eq!(1, ins.current_version_index());
+    assert_eq!(0, ins.previous_version_index());
+
+    // previous current can still be removed by hand
+    ins.remove_version(0).unwrap();
+    assert_eq!(usize::MAX, ins.previous_version_index());
+    assert_eq!(0, ins.current_version_index());
+    let ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert_eq!(usize::MAX, ins.previous_version_index());
+    assert_eq!("hash1", ins.current_version().unwrap().nice_name());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pin_version() {
+    use lifeblood_manager::RetentionPolicy;
+
+    let actual = common::copy_fixture("pin");
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(ins.iter_versions().all(|x| !x.is_pinned()));
+    ins.pin_version(2).unwrap();
+    assert!(ins.version(2).unwrap().is_pinned());
+    assert!(ins.pin_version(3).is_err());
+
+    // pinned is remembered
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.is_base_path_tainted());
+    assert_eq!(3, ins.version_count());
+    assert!(ins.version(2).unwrap().is_pinned());
+    assert!(!ins.version(1).unwrap().is_pinned());
+
+    // pinned cannot be removed or pruned
+    assert!(ins.remove_version(2).is_err());
+    ins.set_retention_policy(RetentionPolicy {
+        keep_newest: Some(0),
+        keep_days: None,
+    });
+    assert_eq!(vec![1], ins.versions_to_prune());
+
+    ins.unpin_version(2).unwrap();
+    assert!(!ins.version(2).unwrap().is_pinned());
+    assert_eq!(vec![1, 2], ins.versions_to_prune());
+    ins.remove_version(2).unwrap();
+    assert_eq!(2, ins.version_count());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_metadata_v2() {
+    use std::path::PathBuf;
+
+    let actual = common::copy_fixture("meta");
+
+    std::fs::write(
+        actual.join("hash3").join("meta.info"),
+        "format_version = 2\n\
+         nice_name = \"hash3\"\n\
+         commit = \"e7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8\"\n\
+         date = \"2023-11-25T01:53:18Z\"\n\
+         source_url = \"https://github.com/pedohorse/lifeblood/archive/refs/tags/v1.0.zip\"\n\
+         source_ref = \"tag:v1.0\"\n\
+         python = \"/usr/bin/python3\"\n\
+         python_version = \"3.11.4\"\n\
+         has_viewer = false\n\
+         manager_build = \"test build\"\n\
+         install_duration_secs = 42.5\n\
+         some_field_from_the_future = 1\n",
+    )
+    .unwrap();
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert_eq!(3, ins.version_count());
+
+    // v1
+    let ver = ins.version(1).unwrap();
+    assert_eq!("hash1", ver.nice_name());
+    assert_eq!("a7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8", ver.source_commit());
+    assert_eq!(None, ver.source_url());
+    assert_eq!(None, ver.python_version());
+    assert_eq!(None, ver.install_duration());
+    assert_eq!("", ver.notes());
+
+    // v2
+    let ver = ins.version(2).unwrap();
+    assert_eq!("hash3", ver.nice_name());
+    assert_eq!("e7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8", ver.source_commit());
+    assert_eq!(
+        "2023-11-25 01:53:18",
+        ver.date().format("%Y-%m-%d %H:%M:%S").to_string()
+    );
+    assert_eq!(
+        Some("https://github.com/pedohorse/lifeblood/archive/refs/tags/v1.0.zip"),
+        ver.source_url()
+    );
+    assert_eq!(Some(&SourceRef::Tag("v1.0".to_owned())), ver.source_ref());
+    assert_eq!(Some(PathBuf::from("/usr/bin/python3").as_path()), ver.python());
+    assert_eq!(Some("3.11.4"), ver.python_version());
+    assert_eq!(Some("test build"), ver.manager_build());
+    assert_eq!(Some(42.5), ver.install_duration().map(|x| x.as_secs_f64()));
+    assert_eq!("", ver.notes());
+
+    // notes are saved, v1 is upgraded to v2 on save
+    ins.set_version_notes(1, "known good\nfor sure".to_owned()).unwrap();
+    ins.set_version_notes(2, "meh".to_owned()).unwrap();
+    assert!(std::fs::read_to_string(actual.join("hash1").join("meta.info"))
+        .unwrap()
+        .starts_with("format_version = 2"));
+
+    let ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    let ver = ins.version(1).unwrap();
+    assert_eq!("known good\nfor sure", ver.notes());
+    assert_eq!("a7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8", ver.source_commit());
+    assert_eq!(
+        "2023-10-25 03:53:18",
+        ver.date().format("%Y-%m-%d %H:%M:%S").to_string()
+    );
+    let ver = ins.version(2).unwrap();
+    assert_eq!("meh", ver.notes());
+    assert_eq!(Some("3.11.4"), ver.python_version());
+    assert_eq!(Some(&SourceRef::Tag("v1.0".to_owned())), ver.source_ref());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_version() {
+    use std::path::PathBuf;
+
+    let actual = common::copy_fixture("verify");
+
+    let ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(ins.verify_version(3).is_err());
+
+    // test data has no real venvs
+    let report = ins.verify_version(0).unwrap();
+    assert!(!report.is_ok());
+    for name in ["python starts", "import lifeblood", "pip check", "lifeblood launcher"] {
+        assert!(!report.check(name).unwrap().passed(), "{} should fail", name);
+    }
+    assert!(report.check("import lifeblood_viewer").is_none());
+
+    // fake venv with system python
+    let system_python = PathBuf::from("/usr/bin/python3");
+    if !system_python.exists() {
+        common::skip("test_verify_version", "no /usr/bin/python3");
+        return;
+    }
+    std::fs::create_dir_all(actual.join("hash2").join("venv").join("bin")).unwrap();
+    std::os::unix::fs::symlink(
+        &system_python,
+        actual.join("hash2").join("venv").join("bin").join("python"),
+    )
+    .unwrap();
+
+    let report = ins.verify_version(0).unwrap();
+    for name in [
+        "python starts",
+        "import lifeblood",
+        "lifeblood launcher",
+        "lifeblood_viewer launcher",
+    ] {
+        assert!(report.check(name).unwrap().passed(), "{} should pass", name);
+    }
+
+    // launchers of current version are not other versions' business
+    let report = ins.verify_version(1).unwrap();
+    assert!(!report.check("python starts").unwrap().passed());
+    assert!(report.check("lifeblood launcher").is_none());
+
+    // but launcher that does not point to the version it's supposed to run is reported
+    std::fs::remove_file(actual.join("current")).unwrap();
+    std::os::unix::fs::symlink("hash1", actual.join("current")).unwrap();
+    std::fs::create_dir_all(actual.join("hash1").join("venv").join("bin")).unwrap();
+    std::os::unix::fs::symlink(
+        &system_python,
+        actual.join("hash1").join("venv").join("bin").join("python"),
+    )
+    .unwrap();
+    let ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert_eq!(1, ins.current_version_index());
+    assert!(ins.verify_version(1).unwrap().check("lifeblood launcher").unwrap().passed());
+    std::fs::write(actual.join("lifeblood"), "#!/bin/sh\nexec $cwd/hash2/venv/bin/python\n")
+        .unwrap();
+    assert!(!ins.verify_version(1).unwrap().check("lifeblood launcher").unwrap().passed());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_repair_version() {
+    use lifeblood_manager::installation_helpers::get_python_command;
+    use lifeblood_manager::InstallError;
+
+    let actual = common::copy_fixture("repair");
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    // no stored requirements - nothing to repair with
+    assert!(ins.repair_version(0).is_err());
+    assert!(ins.repair_version(3).is_err());
+
+    if get_python_command().is_none() {
+        common::skip("test_repair_version", "no python found");
+        return;
+    }
+    let broken_venv_marker = actual.join("hash2").join("venv").join("empty");
+    let has_venv_leftovers = || {
+        std::fs::read_dir(actual.join("hash2"))
+            .unwrap()
+            .any(|x| x.unwrap().file_name().to_string_lossy().starts_with("__broken_venv_"))
+    };
+
+    // python that the version does not support is refused before venv is touched
+    let meta_path = actual.join("hash2").join("meta.info");
+    let meta_v1 = std::fs::read_to_string(&meta_path).unwrap();
+    std::fs::write(
+        &meta_path,
+        concat!(
+            "format_version = 2\nnice_name = \"hash2\"\n",
+            "commit = \"b7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8\"\n",
+            "date = \"2023-10-25T02:53:18Z\"\npython_requires = \"<3\"\n",
+        ),
+    )
+    .unwrap();
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    std::fs::write(actual.join("hash2").join("requirements.txt"), "").unwrap();
+    match ins.repair_version(0).unwrap_err() {
+        InstallError::UnsupportedPython { .. } => (),
+        x => panic!("unexpected error {:?}", x),
+    }
+    assert!(broken_venv_marker.exists());
+    // version is also current of a channel, whose launchers are repaired too
+    std::fs::write(&meta_path, format!("{}\nbranch:dev\n", meta_v1.trim())).unwrap();
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.make_version_channel_current(0).unwrap();
+
+    // venv that cannot be remade is put back as it was
+    std::fs::write(actual.join("hash2").join("requirements.txt"), "!!!\n").unwrap();
+    assert!(ins.repair_version(0).is_err());
+    assert!(broken_venv_marker.exists());
+    assert!(!has_venv_leftovers());
+
+    // empty requirements, so that pip does not need network
+    std::fs::write(actual.join("hash2").join("requirements.txt"), "").unwrap();
+    std::fs::remove_file(actual.join("lifeblood")).unwrap();
+    std::fs::remove_file(actual.join("lifeblood-dev")).unwrap();
+    assert!(!ins.verify_version(0).unwrap().check("python starts").unwrap().passed());
+
+    ins.repair_version(0).unwrap();
+    let report = ins.verify_version(0).unwrap();
+    for name in ["python starts", "import lifeblood", "pip check", "lifeblood launcher"] {
+        assert!(report.check(name).unwrap().passed(), "{} should pass", name);
+    }
+    assert!(actual.join("lifeblood").exists());
+    assert!(actual.join("lifeblood-dev").exists());
+    assert!(!broken_venv_marker.exists());
+    assert!(!has_venv_leftovers());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_make_current_atomic() {
+    let actual = common::copy_fixture("atomic");
+
+    let has_temp_leftovers = || {
+        std::fs::read_dir(&actual).unwrap().any(|x| {
+            let name = x.unwrap().file_name().to_string_lossy().to_string();
+            name.starts_with(".current.")
+                || (name.starts_with(".lifeblood") && name != ".lifeblood-manager.generation")
+        })
+    };
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.make_version_current(1).unwrap();
+    assert_eq!("hash1", actual.join("current").read_link().unwrap().to_string_lossy());
+    assert!(!has_temp_leftovers());
+
+    // something that cannot be replaced by a link
+    std::fs::remove_file(actual.join("current")).unwrap();
+    std::fs::create_dir(actual.join("current")).unwrap();
+    std::fs::write(actual.join("current").join("something"), "").unwrap();
+
+    assert!(ins.make_version_current(2).is_err());
+    assert_eq!(1, ins.current_version_index());
+    assert!(actual.join("current").join("something").exists());
+    assert!(!has_temp_leftovers());
+
+    let ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.is_base_path_tainted());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_base_dir_lock() {
+    use lifeblood_manager::{BaseDirLockSettings, InstallEvent, InstallPhase};
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    let actual = common::copy_fixture("lock");
+    let lock_path = actual.join(".lifeblood-manager.lock");
+
+    // archive of already installed commit, so installing it is quick
+    let zip_path = actual.join("hash1.zip");
+    {
+        let options = zip::write::FileOptions::default();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.set_comment("a7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8");
+        zip.start_file("lifeblood-dev/entry.py", options).unwrap();
+        zip.write_all(b"print('hello')\n").unwrap();
+        zip.finish().unwrap();
+    }
+
+    // while one manager is installing - another cannot touch the dir
+    let other = Arc::new(Mutex::new(InstallationsData::from_dir(actual.clone()).unwrap()));
+    let other_results = Arc::new(Mutex::new(Vec::new()));
+    let lock_contents = Arc::new(Mutex::new(String::new()));
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    {
+        let other = other.clone();
+        let other_results = other_results.clone();
+        let lock_contents = lock_contents.clone();
+        let lock_path = lock_path.clone();
+        ins.set_progress_callback(Some(Arc::new(move |event: &InstallEvent| {
+            if *event != InstallEvent::Phase(InstallPhase::Unpack) {
+                return;
+            }
+            *lock_contents.lock().unwrap() = std::fs::read_to_string(&lock_path).unwrap();
+            let mut other = other.lock().unwrap();
+            other_results.lock().unwrap().push(other.pin_version(0));
+            other.set_base_dir_lock_settings(BaseDirLockSettings {
+                wait: true,
+                wait_timeout_secs: Some(1),
+            });
+            other_results.lock().unwrap().push(other.pin_version(0));
+        })));
+    }
+    assert_eq!(1, ins.install_from_zip(&zip_path, false, None).unwrap());
+    assert!(!lock_path.exists());
+
+    let other_results = std::mem::take(&mut *other_results.lock().unwrap());
+    assert_eq!(2, other_results.len());
+    let err = other_results[0].as_ref().unwrap_err();
+    assert_eq!(std::io::ErrorKind::WouldBlock, err.kind());
+    assert!(err
+        .to_string()
+        .contains(&format!("locked by process {} ", std::process::id())));
+    assert_eq!(
+        std::io::ErrorKind::TimedOut,
+        other_results[1].as_ref().unwrap_err().kind()
+    );
+    assert!(!other.lock().unwrap().version(0).unwrap().is_pinned());
+
+    // lock released - others can go on, once they see what was done under it
+    let err = other.lock().unwrap().pin_version(0).unwrap_err();
+    assert!(err.to_string().contains("reload"), "{}", err);
+    let mut other = InstallationsData::from_dir(actual.clone()).unwrap();
+    other.pin_version(0).unwrap();
+    assert!(!lock_path.exists());
+
+    // lock of a process that is long dead is stale, and is taken over
+    let mut dead = std::process::Command::new("true").spawn().unwrap();
+    dead.wait().unwrap();
+    let lock_contents = lock_contents.lock().unwrap().replace(
+        &format!("pid = {}\n", std::process::id()),
+        &format!("pid = {}\n", dead.id()),
+    );
+    assert!(lock_contents.contains(&format!("pid = {}\n", dead.id())));
+    std::fs::write(&lock_path, lock_contents).unwrap();
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.unpin_version(0).unwrap();
+    assert!(!lock_path.exists());
+    assert!(!actual.join(".lifeblood-manager.lock.takeover").exists());
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.version(0).unwrap().is_pinned());
+
+    // another manager changed base path - what's known about it is not trusted any more
+    let mut other = InstallationsData::from_dir(actual.clone()).unwrap();
+    other.make_version_current(1).unwrap();
+    let err = ins.remove_version(1).unwrap_err();
+    assert!(err.to_string().contains("reload"), "{}", err);
+    assert!(actual.join("hash1").exists());
+    assert!(ins.make_version_current(2).is_err());
+    assert_eq!("hash1", actual.join("current").read_link().unwrap().to_string_lossy());
+    assert!(!lock_path.exists());
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.make_version_current(2).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_uv_installer() {
+    use lifeblood_manager::installation_helpers::get_python_command;
+    use lifeblood_manager::{InstallError, InstallerKind, InstallerSettings};
+    use std::os::unix::fs::PermissionsExt;
+
+    let actual = common::copy_fixture("uv");
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    std::fs::write(actual.join("hash2").join("requirements.txt"), "").unwrap();
+
+    // uv is asked for, but it's not there
+    ins.set_installer_settings(InstallerSettings {
+        backend: InstallerKind::Uv,
+        uv_path: Some(absolute(actual.join("no_uv_here")).unwrap()),
+        ..InstallerSettings::default()
+    });
+    match ins.repair_version(0).unwrap_err() {
+        InstallError::Venv(e) => assert_eq!(std::io::ErrorKind::NotFound, e.kind()),
+        x => panic!("unexpected error {:?}", x),
+    }
+
+    if get_python_command().is_none() {
+        common::skip("test_uv_installer", "no python found");
+        return;
+    }
+    // fake uv, that logs how it's called, and makes venv with plain python
+    let log_path = absolute(actual.join("uv.log")).unwrap();
+    let uv_path = absolute(actual.join("uv")).unwrap();
+    std::fs::write(
+        &uv_path,
+        format!(
+            "#!/bin/sh\n\
+             echo \"$@\" >> {:?}\n\
+             eval \"python=\\${{$(($# - 1))}}\"\n\
+             if [ \"$1\" = venv ]; then exec \"$python\" -m venv venv; fi\n",
+            log_path
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&uv_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    ins.set_installer_settings(InstallerSettings {
+        backend: InstallerKind::Auto,
+        uv_path: Some(uv_path),
+        index_url: Some("http://pypi.local/simple".to_owned()),
+        trusted_hosts: vec!["pypi.local".to_owned()],
+        ..InstallerSettings::default()
+    });
+    ins.repair_version(0).unwrap();
+
+    let log = std::fs::read_to_string(&log_path).unwrap();
+    let calls = log.lines().collect::<Vec<&str>>();
+    assert_eq!(2, calls.len(), "{}", log);
+    let index_args = "--index-url http://pypi.local/simple --allow-insecure-host pypi.local";
+    assert!(calls[0].starts_with(&format!("venv --seed {} --python ", index_args)));
+    assert!(calls[1].starts_with(&format!("pip install {} --python ", index_args)));
+    assert!(calls[1].ends_with("requirements.txt"));
+    // the rest of installation is the same whatever installer is
+    let report = ins.verify_version(0).unwrap();
+    for name in ["python starts", "import lifeblood", "lifeblood launcher"] {
+        assert!(report.check(name).unwrap().passed(), "{} should pass", name);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_offline_wheelhouse() {
+    use lifeblood_manager::installation_helpers::get_python_command;
+    use lifeblood_manager::{InstallError, InstallerKind, InstallerSettings};
+    use std::path::PathBuf;
+
+    let actual = common::copy_fixture("wheelhouse");
+    std::fs::create_dir(actual.join("wheels")).unwrap();
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.set_installer_settings(InstallerSettings {
+        backend: InstallerKind::Pip,
+        index_url: Some("http://127.0.0.1:9/simple".to_owned()),
+        wheelhouse: Some(PathBuf::from("wheels")),
+        ..InstallerSettings::default()
+    });
+    // relative wheelhouse is relative to base path
+    assert_eq!(
+        Some(absolute(actual.join("wheels")).unwrap()),
+        ins.installer_settings().wheelhouse
+    );
+
+    if get_python_command().is_none() {
+        common::skip("test_offline_wheelhouse", "no python found");
+        return;
+    }
+    // nothing is in the wheelhouse, and no index is even tried
+    let requirements_path = actual.join("hash2").join("requirements.txt");
+    std::fs::write(&requirements_path, "no-such-package-here\n").unwrap();
+    let err = ins.repair_version(0).unwrap_err();
+    assert!(matches!(err, InstallError::Pip(_)), "{:?}", err);
+
+    std::fs::write(&requirements_path, "").unwrap();
+    ins.repair_version(0).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_requirements_lock() {
+    use lifeblood_manager::installation_helpers::get_python_command;
+    use lifeblood_manager::{InstallerKind, InstallerSettings};
+    use std::path::PathBuf;
+
+    let actual = common::copy_fixture("freeze");
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    // versions installed before locks were recorded
+    assert!(ins.version(0).unwrap().lock_file().is_none());
+
+    let python = match get_python_command() {
+        Some(x) => x,
+        None => {
+            common::skip("test_requirements_lock", "no python found");
+            return;
+        }
+    };
+
+    // two versions of a dependency to choose from, no index is needed
+    std::fs::create_dir(actual.join("wheels")).unwrap();
+    for version in ["1.0", "2.0"] {
+        make_test_wheel(&actual.join("wheels"), version);
+    }
+    ins.set_installer_settings(InstallerSettings {
+        backend: InstallerKind::Pip,
+        wheelhouse: Some(PathBuf::from("wheels")),
+        ..InstallerSettings::default()
+    });
+
+    // newest dependency is installed, and that is recorded
+    let zip_path = make_test_archive(&actual, "f1a57b10cc0de093d1c911f0a8a310f5fe49ddf8");
+    let idx = ins.install_from_zip(&zip_path, false, Some(&python)).unwrap();
+    let lock_file = ins.version(idx).unwrap().lock_file().unwrap();
+    assert_eq!(
+        absolute(actual.join("f1a57b10cc0de").join("requirements.lock")).unwrap(),
+        lock_file
+    );
+    let lock = std::fs::read_to_string(&lock_file).unwrap();
+    assert!(lock.lines().any(|x| x == "lbtestpkg==2.0"), "{}", lock);
+
+    // lock from "another machine" makes the same requirements resolve to what's locked
+    let other_lock = actual.join("other_machine.lock");
+    std::fs::write(&other_lock, "lbtestpkg==1.0\n").unwrap();
+    ins.set_requirements_lock(Some(other_lock));
+    let zip_path = make_test_archive(&actual, "5ec0d010cc0de093d1c911f0a8a310f5fe49ddf8");
+    let idx = ins.install_from_zip(&zip_path, false, Some(&python)).unwrap();
+    let lock_file = ins.version(idx).unwrap().lock_file().unwrap();
+    let lock = std::fs::read_to_string(&lock_file).unwrap();
+    assert!(lock.lines().any(|x| x == "lbtestpkg==1.0"), "{}", lock);
+
+    // repair keeps locked versions
+    ins.set_requirements_lock(None);
+    std::fs::write(&lock_file, "lbtestpkg==2.0\n").unwrap();
+    ins.repair_version(idx).unwrap();
+    let lock = std::fs::read_to_string(&lock_file).unwrap();
+    assert!(lock.lines().any(|x| x == "lbtestpkg==2.0"), "{}", lock);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_export_import_bundle() {
+    use lifeblood_manager::installation_helpers::get_python_command;
+    use lifeblood_manager::{InstallError, InstallerKind, InstallerSettings};
+    use std::path::PathBuf;
+
+    let online = common::copy_fixture("export");
+    let offline = common::copy_fixture("import");
+
+    let mut ins = InstallationsData::from_dir(online.clone()).unwrap();
+    let bundle_path = absolute(online.join("version.bundle")).unwrap();
+    match ins.export_version(10, &bundle_path).unwrap_err() {
+        InstallError::Other(e) => assert_eq!(std::io::ErrorKind::NotFound, e.kind()),
+        x => panic!("unexpected error {:?}", x),
+    }
+
+    let python = match get_python_command() {
+        Some(x) => x,
+        None => {
+            common::skip("test_export_import_bundle", "no python found");
+            return;
+        }
+    };
+
+    // "online" machine gets packages from its index, here it's a wheelhouse
+    std::fs::create_dir(online.join("wheels")).unwrap();
+    for version in ["1.0", "2.0"] {
+        make_test_wheel(&online.join("wheels"), version);
+    }
+    ins.set_installer_settings(InstallerSettings {
+        backend: InstallerKind::Pip,
+        wheelhouse: Some(PathBuf::from("wheels")),
+        ..InstallerSettings::default()
+    });
+    let locked = online.join("locked.lock");
+    std::fs::write(&locked, "lbtestpkg==1.0\n").unwrap();
+    ins.set_requirements_lock(Some(locked));
+    let zip_path = make_test_archive(&online, "e8b0a7c0de000093d1c911f0a8a310f5fe49ddf8");
+    let idx = ins.install_from_zip(&zip_path, false, Some(&python)).unwrap();
+    ins.set_version_notes(idx, "exported".to_owned()).unwrap();
+    ins.export_version(idx, &bundle_path).unwrap();
+    assert!(bundle_path.exists());
+
+    // "offline" machine's index is unreachable, only bundled wheels are used
+    let mut ins = InstallationsData::from_dir(offline.clone()).unwrap();
+    ins.set_installer_settings(InstallerSettings {
+        backend: InstallerKind::Pip,
+        index_url: Some("http://127.0.0.1:9/simple".to_owned()),
+        ..InstallerSettings::default()
+    });
+    let idx = ins.import_bundle(&bundle_path, Some(&python)).unwrap();
+    assert_eq!(4, ins.version_count());
+    let ver = ins.version(idx).unwrap();
+    assert_eq!("e8b0a7c0de000", ver.nice_name());
+    assert_eq!("e8b0a7c0de000093d1c911f0a8a310f5fe49ddf8", ver.source_commit());
+    assert_eq!("exported", ver.notes());
+    assert!(!ver.has_viewer());
+    // the interpreter venv was made with is recorded, not just the command given
+    let recorded_python = ver.python().unwrap();
+    assert!(recorded_python.is_absolute(), "{:?}", recorded_python);
+    assert!(recorded_python.exists(), "{:?}", recorded_python);
+    let lock = std::fs::read_to_string(ver.lock_file().unwrap()).unwrap();
+    assert!(lock.lines().any(|x| x == "lbtestpkg==1.0"), "{}", lock);
+    let report = ins.verify_version(idx).unwrap();
+    for name in ["python starts", "import lifeblood"] {
+        assert!(report.check(name).unwrap().passed(), "{} should pass", name);
+    }
+
+    // same commit is not imported twice
+    assert_eq!(idx, ins.import_bundle(&bundle_path, Some(&python)).unwrap());
+    assert_eq!(4, ins.version_count());
+}
+
+///
+/// minimal pure python wheel of a package named lbtestpkg
+///
+#[test]
+#[cfg(unix)]
+fn test_host_current() {
+    let actual = common::copy_fixture("host_current");
+
+    let host_link = actual.join(format!("current@{}", sysinfo::System::host_name().unwrap()));
+    let index_of = |ins: &InstallationsData, name: &str| {
+        ins.iter_versions().position(|x| x.nice_name() == name).unwrap()
+    };
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.has_host_current());
+    assert_eq!("hash2", ins.current_version().unwrap().nice_name());
+
+    ins.set_host_current(Some(index_of(&ins, "hash1"))).unwrap();
+    assert!(ins.has_host_current());
+    assert_eq!("hash1", ins.current_version().unwrap().nice_name());
+    assert_eq!("hash1", host_link.read_link().unwrap().to_string_lossy());
+    assert_eq!("hash2", actual.join("current").read_link().unwrap().to_string_lossy());
+
+    // other hosts' links are expected there
+    std::os::unix::fs::symlink("hash3", actual.join("current@some-other-host")).unwrap();
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.is_base_path_tainted());
+    assert!(ins.has_host_current());
+    assert_eq!("hash1", ins.current_version().unwrap().nice_name());
+
+    // only this host's current is switched
+    ins.make_version_current(index_of(&ins, "hash3")).unwrap();
+    assert_eq!("hash3", host_link.read_link().unwrap().to_string_lossy());
+    assert_eq!("hash2", actual.join("current").read_link().unwrap().to_string_lossy());
+    assert_eq!(index_of(&ins, "hash1"), ins.previous_version_index());
+
+    // versions current on any host cannot be removed
+    assert!(ins.remove_version(index_of(&ins, "hash2")).is_err());
+    assert_eq!(3, ins.version_count());
+
+    ins.set_host_current(None).unwrap();
+    assert!(!ins.has_host_current());
+    assert!(!host_link.is_symlink());
+    assert_eq!("hash2", ins.current_version().unwrap().nice_name());
+
+    // broken override falls back to global current
+    std::os::unix::fs::symlink("nonexistent", &host_link).unwrap();
+    let ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.has_host_current());
+    assert_eq!("hash2", ins.current_version().unwrap().nice_name());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_channels() {
+    use lifeblood_manager::RetentionPolicy;
+
+    let actual = common::copy_fixture("channels");
+
+    // versions without saved channel belong to the branch they came from
+    let source_refs = [("hash1", "branch:dev"), ("hash2", "branch:master"), ("hash3", "tag:v1")];
+    for (name, source_ref) in source_refs {
+        let meta_path = actual.join(name).join("meta.info");
+        let meta = std::fs::read_to_string(&meta_path).unwrap();
+        std::fs::write(&meta_path, format!("{}\n{}\n", meta.trim(), source_ref)).unwrap();
+    }
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert_eq!(vec!["dev".to_owned(), "master".to_owned()], ins.channels());
+    assert_eq!(Some("master"), ins.version(0).unwrap().channel());
+    assert_eq!(Some("dev"), ins.version(1).unwrap().channel());
+    assert_eq!(None, ins.version(2).unwrap().channel());
+    assert_eq!(Some(1), ins.channel_latest_version_index("dev"));
+    assert_eq!(None, ins.channel_latest_version_index("nonexistent"));
+    assert_eq!(None, ins.channel_current_version_index("dev"));
+
+    ins.make_version_channel_current(1).unwrap();
+    ins.make_version_channel_current(0).unwrap();
+    assert!(ins.make_version_channel_current(2).is_err());
+    assert_eq!("hash1", actual.join("current-dev").read_link().unwrap().to_string_lossy());
+    assert_eq!("hash2", actual.join("current-master").read_link().unwrap().to_string_lossy());
+    let launcher = std::fs::read_to_string(actual.join("lifeblood-dev")).unwrap();
+    assert!(launcher.contains("current=$cwd/current-dev\n"));
+    // there are no per host channel links, so they are not looked for
+    assert!(!launcher.contains("uname"));
+    // "current" is not affected
+    assert_eq!("hash2", actual.join("current").read_link().unwrap().to_string_lossy());
+    assert_eq!(0, ins.current_version_index());
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(!ins.is_base_path_tainted());
+    assert_eq!(Some(1), ins.channel_current_version_index("dev"));
+    assert_eq!(Some(0), ins.channel_current_version_index("master"));
+
+    // channel current versions are protected like the current one
+    assert!(ins.remove_version(1).is_err());
+    ins.set_retention_policy(RetentionPolicy {
+        keep_newest: Some(0),
+        keep_days: None,
+    });
+    assert_eq!(vec![2], ins.versions_to_prune());
+
+    // channel current follows renamed version
+    ins.rename_version(1, "hash1_renamed".to_owned()).unwrap();
+    assert_eq!(
+        "hash1_renamed",
+        actual.join("current-dev").read_link().unwrap().to_string_lossy()
+    );
+    assert_eq!(Some(1), ins.channel_current_version_index("dev"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_add_viewer() {
+    use lifeblood_manager::DownloadSource;
+    use std::io::Write;
+
+    let actual = common::copy_fixture("addviewer");
+    std::fs::remove_file(actual.join("lifeblood_viewer")).unwrap();
+
+    // viewer requirements are installed into existing venv, so it has to be a real one
+    let venv_path = actual.join("hash3").join("venv");
+    std::fs::remove_dir_all(&venv_path).unwrap();
+    match std::process::Command::new("python3")
+        .args(["-m", "venv"])
+        .arg(&venv_path)
+        .status()
+    {
+        Ok(status) if status.success() => (),
+        _ => {
+            common::skip("test_add_viewer", "python3 cannot make venvs");
+            return;
+        }
+    }
+
+    // fake archive of already installed commit, only viewer parts matter
+    let mut zip_data = Vec::new();
+    {
+        let options = zip::write::FileOptions::default();
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+        zip.set_comment("e7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8");
+        zip.start_file("lifeblood-e7a01d7c/src/lifeblood_viewer/__init__.py", options)
+            .unwrap();
+        zip.start_file("lifeblood-e7a01d7c/pkg_lifeblood_viewer/setup.cfg", options)
+            .unwrap();
+        zip.write_all(b"[options]\ninstall_requires =\n    lifeblood\n\n")
+            .unwrap();
+        zip.finish().unwrap();
+    }
+    let (port, requests) = common::serve_file(zip_data);
+
+    // version is also current of a channel, that gets viewer launcher too
+    let meta_path = actual.join("hash3").join("meta.info");
+    let meta = std::fs::read_to_string(&meta_path).unwrap();
+    std::fs::write(&meta_path, format!("{}\nbranch:dev\n", meta.trim())).unwrap();
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.set_download_source(DownloadSource {
+        base_url_template: format!("http://127.0.0.1:{}/{{owner}}/{{repo}}", port),
+        ..DownloadSource::default()
+    });
+    assert!(!ins.version(2).unwrap().has_viewer());
+    ins.make_version_channel_current(2).unwrap();
+    assert!(!actual.join("lifeblood_viewer-dev").exists());
+
+    ins.add_viewer(2).unwrap();
+    assert!(ins.version(2).unwrap().has_viewer());
+    assert!(actual.join("lifeblood_viewer").exists());
+    let launcher = std::fs::read_to_string(actual.join("lifeblood_viewer-dev")).unwrap();
+    assert!(launcher.contains("current=$cwd/current-dev\n"));
+    assert!(actual.join("hash3").join("requirements_viewer.txt").exists());
+    {
+        let requests = requests.lock().unwrap();
+        assert_eq!(1, requests.len());
+        assert_eq!(
+            "GET /pedohorse/lifeblood/archive/e7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8.zip HTTP/1.1",
+            requests[0][0]
+        );
+    }
+
+    // viewer is remembered, and the rest is not reinstalled
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert!(ins.version(2).unwrap().has_viewer());
+    assert!(actual.join("hash3").join("venv").join("bin").join("python").exists());
+    let idx = ins
+        .download_new_version(&SourceRef::Commit("e7a01d7c".to_owned()), true, None)
+        .unwrap();
+    assert_eq!(2, idx);
+    assert_eq!(3, ins.version_count());
+    assert_eq!(1, requests.lock().unwrap().len());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_install_progress_events() {
+    use lifeblood_manager::{DownloadSource, InstallEvent, InstallPhase};
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    let actual = common::copy_fixture("progress");
+
+    // fake branch archive of already installed commit
+    let mut zip_data = Vec::new();
+    {
+        let options = zip::write::FileOptions::default();
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+        zip.set_comment("a7a01d7c9ccc0093d1c911f0a8a310f5fe49ddf8");
+        zip.add_directory("lifeblood-dev/", options).unwrap();
+        zip.start_file("lifeblood-dev/entry.py", options).unwrap();
+        zip.write_all(b"print('hello')\n").unwrap();
+        zip.finish().unwrap();
+    }
+    let zip_size = zip_data.len() as u64;
+    let (port, _) = common::serve_file(zip_data);
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.set_download_source(DownloadSource {
+        base_url_template: format!("http://127.0.0.1:{}/{{owner}}/{{repo}}", port),
+        ..DownloadSource::default()
+    });
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_to_cb = events.clone();
+    ins.set_progress_callback(Some(Arc::new(move |event: &InstallEvent| {
+        events_to_cb.lock().unwrap().push(event.clone());
+    })));
+
+    let idx = ins
+        .download_new_version(&SourceRef::Branch("dev".to_owned()), false, None)
+        .unwrap();
+    assert_eq!(1, idx);
+
+    let events = std::mem::take(&mut *events.lock().unwrap());
+    assert_eq!(InstallEvent::Phase(InstallPhase::Download), events[0]);
+    assert!(events.contains(&InstallEvent::Download {
+        downloaded: zip_size,
+        total: Some(zip_size)
+    }));
+    let unpack_events: Vec<&InstallEvent> = events
+        .iter()
+        .skip_while(|x| **x != InstallEvent::Phase(InstallPhase::Unpack))
+        .skip(1)
+        .take(2)
+        .collect();
+    assert_eq!(
+        vec![
+            &InstallEvent::Unpack { done: 1, total: 2 },
+            &InstallEvent::Unpack { done: 2, total: 2 }
+        ],
+        unpack_events
+    );
+    assert_eq!(Some(&InstallEvent::Finished(Ok(1))), events.last());
+
+    // failures are reported too
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_to_cb = events.clone();
+    ins.set_progress_callback(Some(Arc::new(move |event: &InstallEvent| {
+        events_to_cb.lock().unwrap().push(event.clone());
+    })));
+    assert!(ins
+        .install_from_zip(&actual.join("nonexistent.zip"), false, None)
+        .is_err());
+    let events = events.lock().unwrap();
+    assert_eq!(1, events.len());
+    assert!(matches!(events[0], InstallEvent::Finished(Err(_))));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_cancel_installation() {
+    use lifeblood_manager::{
+        CancellationToken, DownloadSource, InstallError, InstallEvent, InstallPhase,
+    };
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    let actual = common::copy_fixture("cancel");
+
+    // fake branch archive of a new commit
+    let mut zip_data = Vec::new();
+    {
+        let options = zip::write::FileOptions::default();
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+        zip.set_comment("ca9ce11edc0de093d1c911f0a8a310f5fe49ddf8");
+        zip.start_file("lifeblood-dev/entry.py", options).unwrap();
+        zip.write_all(b"print('hello')\n").unwrap();
+        zip.start_file("lifeblood-dev/src/lifeblood/__init__.py", options)
+            .unwrap();
+        for pkg in ["pkg_lifeblood", "pkg_lifeblood_viewer"] {
+            zip.start_file(format!("lifeblood-dev/{}/setup.cfg", pkg), options)
+                .unwrap();
+            zip.write_all(b"[options]\ninstall_requires =\n    lifeblood\n\n")
+                .unwrap();
+        }
+        zip.finish().unwrap();
+    }
+    let (port, requests) = common::serve_file(zip_data);
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.set_download_source(DownloadSource {
+        base_url_template: format!("http://127.0.0.1:{}/{{owner}}/{{repo}}", port),
+        ..DownloadSource::default()
+    });
+    let mut cache_settings = ins.download_cache_settings().clone();
+    cache_settings.enabled = false;
+    ins.set_download_cache_settings(cache_settings);
+
+    // cancelled before start - nothing is even downloaded
+    let token = CancellationToken::new();
+    token.cancel();
+    ins.set_cancellation_token(Some(token));
+    let err = ins
+        .download_new_version(&SourceRef::Branch("dev".to_owned()), false, None)
+        .unwrap_err();
+    assert!(matches!(err, InstallError::Cancelled));
+    assert_eq!(std::io::ErrorKind::Interrupted, err.kind());
+    assert_eq!(0, requests.lock().unwrap().len());
+
+    // cancelled in the middle of installation - it's rolled back
+    let token = CancellationToken::new();
+    let token_to_cb = token.clone();
+    ins.set_cancellation_token(Some(token));
+    ins.set_progress_callback(Some(Arc::new(move |event: &InstallEvent| {
+        if *event == InstallEvent::Phase(InstallPhase::CopyModules) {
+            token_to_cb.cancel();
+        }
+    })));
+    let err = ins
+        .download_new_version(&SourceRef::Branch("dev".to_owned()), false, None)
+        .unwrap_err();
+    assert!(matches!(err, InstallError::Cancelled), "{:?}", err);
+    assert_eq!(std::io::ErrorKind::Interrupted, err.kind());
+    assert_eq!(1, requests.lock().unwrap().len());
+
+    assert_eq!(3, ins.version_count());
+    assert!(!actual.join("ca9ce11edc0de").exists());
+    // the only trace is manager's own bookkeeping
+    let mut names: Vec<String> = std::fs::read_dir(&actual)
+        .unwrap()
+        .map(|x| x.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|x| x != ".lifeblood-manager.generation")
+        .collect();
+    names.sort();
+    let mut expected_names: Vec<String> = std::fs::read_dir("./tests/data/u_struct3")
+        .unwrap()
+        .map(|x| x.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    expected_names.sort();
+    assert_eq!(expected_names, names);
+    assert_eq!(
+        PathBuf::from("hash2"),
+        std::fs::read_link(actual.join("current")).unwrap()
+    );
+    let ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    assert_eq!(3, ins.version_count());
+
+    // cancelled while download hangs - it is not waited for
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.set_download_source(DownloadSource {
+        base_url_template: format!(
+            "http://127.0.0.1:{}/{{owner}}/{{repo}}",
+            listener.local_addr().unwrap().port()
+        ),
+        ..DownloadSource::default()
+    });
+    let mut cache_settings = ins.download_cache_settings().clone();
+    cache_settings.enabled = false;
+    ins.set_download_cache_settings(cache_settings);
+    let token = CancellationToken::new();
+    ins.set_cancellation_token(Some(token.clone()));
+    let started = std::time::Instant::now();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        token.cancel();
+    });
+    let err = ins
+        .download_new_version(&SourceRef::Branch("dev".to_owned()), false, None)
+        .unwrap_err();
+    assert!(matches!(err, InstallError::Cancelled), "{:?}", err);
+    assert!(started.elapsed() < std::time::Duration::from_secs(30));
+    assert_eq!(3, ins.version_count());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_install_error_variants() {
+    use lifeblood_manager::{DownloadSource, InstallError};
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+
+    let actual = common::copy_fixture("errors");
+
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    let mut cache_settings = ins.download_cache_settings().clone();
+    cache_settings.enabled = false;
+    ins.set_download_cache_settings(cache_settings);
+
+    // nothing listens there
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    ins.set_download_source(DownloadSource {
+        base_url_template: format!("http://127.0.0.1:{}/{{owner}}/{{repo}}", port),
+        ..DownloadSource::default()
+    });
+    let err = ins
+        .download_new_version(&SourceRef::Branch("dev".to_owned()), false, None)
+        .unwrap_err();
+    assert!(matches!(err, InstallError::Download(_)), "{:?}", err);
+    assert!(err.to_string().starts_with("download phase: failed: "));
+    assert!(std::error::Error::source(&err).is_some());
+
+    // interrupted system call is not a cancellation
+    let err = InstallError::from(std::io::Error::from(std::io::ErrorKind::Interrupted));
+    assert!(matches!(err, InstallError::Other(_)), "{:?}", err);
+
+    // not a branch archive
+    let zip_path = actual.join("no_commit.zip");
+    {
+        let options = zip::write::FileOptions::default();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.start_file("lifeblood-dev/entry.py", options).unwrap();
+        zip.finish().unwrap();
+    }
+    let err = ins.install_from_zip(&zip_path, false, None).unwrap_err();
+    assert!(matches!(err, InstallError::Unpack(_)), "{:?}", err);
+
+    // python is too new for that version
+    let python = PathBuf::from("/usr/bin/python3");
+    if !python.exists() {
+        common::skip("test_install_error_variants", "no /usr/bin/python3");
+        return;
+    }
+    let zip_path = actual.join("old_python.zip");
+    {
+        let options = zip::write::FileOptions::default();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.set_comment("01d0000c0de00093d1c911f0a8a310f5fe49ddf8");
+        zip.start_file("lifeblood-dev/entry.py", options).unwrap();
+        zip.start_file("lifeblood-dev/src/lifeblood/__init__.py", options)
+            .unwrap();
+        for pkg in ["pkg_lifeblood", "pkg_lifeblood_viewer"] {
+            zip.start_file(format!("lifeblood-dev/{}/setup.cfg", pkg), options)
+                .unwrap();
+            zip.write_all(b"[options]\npython_requires = <3.0\ninstall_requires =\n    foo\n\n")
+                .unwrap();
+        }
+        zip.finish().unwrap();
+    }
+    let err = ins
+        .install_from_zip(&zip_path, false, Some(&python))
+        .unwrap_err();
+    match err {
+        InstallError::UnsupportedPython { ref requires, .. } => assert_eq!("<3.0", requires),
+        ref x => panic!("unexpected error {:?}", x),
+    }
+    assert_eq!(std::io::ErrorKind::Unsupported, err.kind());
+    assert!(!actual.join("01d0000c0de00").exists());
+    assert_eq!(3, ins.version_count());
+
+    // io error still carries the install error, for code that deals with io errors only
+    let io_err: std::io::Error = err.into();
+    assert!(io_err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<InstallError>()
+        .is_some());
+}
+
+#[cfg(unix)]
+fn make_test_wheel(dir: &std::path::Path, version: &str) {
+    use std::io::Write;
+
+    let options = zip::write::FileOptions::default();
+    let wheel_path = dir.join(format!("lbtestpkg-{}-py3-none-any.whl", version));
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&wheel_path).unwrap());
+    let info_dir = format!("lbtestpkg-{}.dist-info", version);
+    zip.start_file("lbtestpkg/__init__.py", options).unwrap();
+    zip.start_file(format!("{}/METADATA", info_dir), options).unwrap();
+    write!(zip, "Metadata-Version: 2.1\nName: lbtestpkg\nVersion: {}\n", version).unwrap();
+    zip.start_file(format!("{}/WHEEL", info_dir), options).unwrap();
+    zip.write_all(b"Wheel-Version: 1.0\nRoot-Is-Purelib: true\nTag: py3-none-any\n")
+        .unwrap();
+    zip.start_file(format!("{}/RECORD", info_dir), options).unwrap();
+    writeln!(zip, "lbtestpkg/__init__.py,,").unwrap();
+    for name in ["METADATA", "WHEEL", "RECORD"] {
+        writeln!(zip, "{}/{},,", info_dir, name).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+///
+/// fake lifeblood branch archive of given commit, requiring lbtestpkg
+///
+#[cfg(unix)]
+fn make_test_archive(dir: &std::path::Path, commit: &str) -> std::path::PathBuf {
+    use std::io::Write;
+
+    let zip_path = dir.join(format!("{}.zip", commit));
+    let options = zip::write::FileOptions::default();
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+    zip.set_comment(commit);
+    zip.start_file("lifeblood-dev/entry.py", options).unwrap();
+    zip.start_file("lifeblood-dev/src/lifeblood/__init__.py", options)
+        .unwrap();
+    for pkg in ["pkg_lifeblood", "pkg_lifeblood_viewer"] {
+        zip.start_file(format!("lifeblood-dev/{}/setup.cfg", pkg), options)
+            .unwrap();
+        zip.write_all(b"[options]\ninstall_requires =\n    lbtestpkg\n\n")
+            .unwrap();
+    }
+    zip.finish().unwrap();
+    zip_path
+}
@@ -0,0 +1,132 @@
+use lifeblood_manager::{PackageMetadata, PythonVersion, VersionSpecifiers};
+use std::fs;
+
+fn v(text: &str) -> PythonVersion {
+    text.parse().unwrap()
+}
+
+fn spec(text: &str) -> VersionSpecifiers {
+    text.parse().unwrap()
+}
+
+#[test]
+fn test_python_version_order() {
+    let ordered = [
+        "1.0.dev1", "1.0a1", "1.0a2.dev1", "1.0a2", "1.0b1", "1.0rc1", "1.0", "1.0.post1",
+        "1.1", "1!0.1",
+    ];
+    for pair in ordered.windows(2) {
+        assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+    }
+    assert_eq!(v("3.8"), v("3.8.0"));
+    assert_eq!(v("1.0-1"), v("1.0.post1"));
+    assert_eq!(v("1.0alpha.1"), v("1.0a1"));
+    assert_eq!("3.13.0rc1", v("3.13.0RC1").to_string());
+    assert!("3.x".parse::<PythonVersion>().is_err());
+}
+
+#[test]
+fn test_version_specifiers() {
+    let s = spec(">=3.8,<3.13");
+    assert!(!s.contains(&v("3.7.17")));
+    assert!(s.contains(&v("3.8")));
+    assert!(s.contains(&v("3.12.4")));
+    assert!(!s.contains(&v("3.13.0")));
+    assert!(!s.contains(&v("3.13.0rc1")));
+    assert!(s.contains(&v("3.12.0rc1")));
+
+    // new pythons are fine as long as they are declared to be
+    assert!(spec(">=3.8").contains(&v("3.42.0")));
+
+    assert!(spec("~=3.10").contains(&v("3.99")));
+    assert!(!spec("~=3.10").contains(&v("4.0")));
+    assert!(spec("~=3.10.2").contains(&v("3.10.9")));
+    assert!(!spec("~=3.10.2").contains(&v("3.11.0")));
+
+    assert!(spec("==3.11.*").contains(&v("3.11.5")));
+    assert!(!spec("==3.11.*").contains(&v("3.1.5")));
+    assert!(!spec("!=3.9.*, >=3.8").contains(&v("3.9.1")));
+    assert!(spec("== 3.9").contains(&v("3.9.0")));
+    assert!(!spec(">3.8").contains(&v("3.8.post1")));
+    assert!(spec(">3.8").contains(&v("3.8.1")));
+
+    assert!(spec("").contains(&v("2.7")));
+    assert!("3.8".parse::<VersionSpecifiers>().is_err());
+    assert!(">=3.8.*".parse::<VersionSpecifiers>().is_err());
+    assert!("~=3".parse::<VersionSpecifiers>().is_err());
+    assert_eq!(">=3.8,<3.13", spec(" >=3.8 , <3.13 ").to_string());
+}
+
+#[test]
+fn test_package_metadata_from_setupcfg() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("setup.cfg"),
+        "[metadata]\n\
+         name = lifeblood\n\
+         # python_requires = <3.0\n\
+         \n\
+         [options]\n\
+         package_dir =\n    =src\n\
+         install_requires =\n\
+         \x20   # comment\n\
+         \x20   numpy\n\
+         \n\
+         \x20   lifeblood_viewer == 1.0\n\
+         \x20   psutil>=5.8 ; sys_platform == \"linux\"\n\
+         Python_Requires: >=3.8, <3.13\n\
+         \n\
+         [options.packages.find]\n\
+         where = src\n",
+    )
+    .unwrap();
+    let metadata = PackageMetadata::from_package_dir(dir.path()).unwrap();
+    assert_eq!(
+        vec!["numpy", "psutil>=5.8 ; sys_platform == \"linux\""],
+        metadata.requirements
+    );
+    assert!(metadata.python_requires.contains(&v("3.12")));
+    assert!(!metadata.python_requires.contains(&v("3.13")));
+
+    fs::write(
+        dir.path().join("setup.cfg"),
+        "[options]\npython_requires = >=3.bad\n",
+    )
+    .unwrap();
+    let err = PackageMetadata::from_package_dir(dir.path()).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+}
+
+#[test]
+fn test_package_metadata_from_pyproject() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(
+        std::io::ErrorKind::NotFound,
+        PackageMetadata::from_package_dir(dir.path())
+            .unwrap_err()
+            .kind()
+    );
+
+    fs::write(
+        dir.path().join("pyproject.toml"),
+        "[project]\n\
+         name = \"lifeblood\"\n\
+         requires-python = \">=3.9\"\n\
+         dependencies = [\"grandalf==0.7\", \"lifeblood\", \"requests\"]\n",
+    )
+    .unwrap();
+    let metadata = PackageMetadata::from_package_dir(dir.path()).unwrap();
+    assert_eq!(vec!["grandalf==0.7", "requests"], metadata.requirements);
+    assert!(!metadata.python_requires.contains(&v("3.8")));
+    assert!(metadata.python_requires.contains(&v("3.14")));
+
+    // setup.cfg wins, whatever is missing in it is taken from pyproject
+    fs::write(
+        dir.path().join("setup.cfg"),
+        "[options]\ninstall_requires =\n    numpy\n",
+    )
+    .unwrap();
+    let metadata = PackageMetadata::from_package_dir(dir.path()).unwrap();
+    assert_eq!(vec!["numpy"], metadata.requirements);
+    assert!(!metadata.python_requires.contains(&v("3.8")));
+}
@@ -0,0 +1,113 @@
+use lifeblood_manager::{InstallError, InstallationsData, PythonDiscovery, PythonSource};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+mod common;
+
+#[cfg(unix)]
+fn make_fake_python(dir: &Path, name: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn test_python_discovery() {
+    // this test changes env, so it lives in it's own test binary
+    let home = tempfile::tempdir().unwrap();
+    let bin_dir = home.path().join("bin");
+    std::fs::create_dir(&bin_dir).unwrap();
+    make_fake_python(&bin_dir, "python", "exit 1");
+    make_fake_python(&bin_dir, "python3.98", "echo Python 3.98.1");
+    make_fake_python(&bin_dir, "python3.99", "echo Python 3.99.2rc1");
+    std::os::unix::fs::symlink("python3.99", bin_dir.join("python3")).unwrap();
+    make_fake_python(&bin_dir, "not_python", "echo Python 3.99.9");
+    let pyenv_bin_dir = home.path().join(".pyenv").join("versions").join("3.97.0").join("bin");
+    std::fs::create_dir_all(&pyenv_bin_dir).unwrap();
+    make_fake_python(&pyenv_bin_dir, "python", "echo Python 3.97.0");
+
+    std::env::set_var("HOME", home.path());
+    std::env::set_var("PATH", format!("{}:{}", bin_dir.display(), bin_dir.display()));
+    std::env::remove_var("PYTHON_BIN");
+    std::env::remove_var("PYENV_ROOT");
+    std::env::remove_var("CONDA_PREFIX");
+
+    let discovery = PythonDiscovery::run();
+    let ours = discovery
+        .candidates()
+        .iter()
+        .filter(|x| x.path().starts_with(home.path()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            bin_dir.join("python"),
+            bin_dir.join("python3"),
+            bin_dir.join("python3.98"),
+            bin_dir.join("python3.99"),
+            pyenv_bin_dir.join("python"),
+        ],
+        ours.iter().map(|x| x.path().to_path_buf()).collect::<Vec<PathBuf>>()
+    );
+    assert_eq!(PythonSource::Path, ours[0].source());
+    assert_eq!(PythonSource::Pyenv, ours[4].source());
+    assert_eq!(None, ours[0].version());
+    assert_eq!(Some("3.99.2rc1"), ours[1].version());
+    assert_eq!(Some("3.97.0"), ours[4].version());
+
+    let requires = ">=3.97".parse().unwrap();
+    assert!(ours[0]
+        .rejection_reason(&requires)
+        .unwrap()
+        .starts_with("does not run"));
+    assert_eq!(None, ours[1].rejection_reason(&requires));
+    // python3 link was found first, so python3.99 is the same python
+    assert!(ours[3]
+        .rejection_reason(&requires)
+        .unwrap()
+        .starts_with("same interpreter as"));
+
+    // newest of suitable ones is chosen
+    let chosen = discovery.choose(&requires).unwrap();
+    assert_eq!(bin_dir.join("python3"), chosen.path());
+    let chosen = discovery.choose(&"<3.99,>=3.97".parse().unwrap()).unwrap();
+    assert_eq!(bin_dir.join("python3.98"), chosen.path());
+    assert!(discovery.choose(&"==1.0".parse().unwrap()).is_none());
+
+    // installation does not even start venv if nothing suitable is found
+    let actual = common::copy_fixture("discovery");
+
+    let zip_path = actual.join("ancient_python.zip");
+    {
+        let options = zip::write::FileOptions::default();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.set_comment("a9c1e9700c0de093d1c911f0a8a310f5fe49ddf8");
+        zip.start_file("lifeblood-dev/entry.py", options).unwrap();
+        zip.start_file("lifeblood-dev/src/lifeblood/__init__.py", options)
+            .unwrap();
+        for pkg in ["pkg_lifeblood", "pkg_lifeblood_viewer"] {
+            zip.start_file(format!("lifeblood-dev/{}/setup.cfg", pkg), options)
+                .unwrap();
+            zip.write_all(b"[options]\npython_requires = ==1.0\ninstall_requires =\n    foo\n\n")
+                .unwrap();
+        }
+        zip.finish().unwrap();
+    }
+    let mut ins = InstallationsData::from_dir(actual.clone()).unwrap();
+    ins.set_auto_select_python(true);
+    let err = ins.install_from_zip(&zip_path, false, None).unwrap_err();
+    match err {
+        InstallError::UnsupportedPython {
+            ref version,
+            ref requires,
+        } => {
+            assert_eq!(&None, version);
+            assert_eq!("==1.0", requires);
+        }
+        ref x => panic!("unexpected error {:?}", x),
+    }
+    assert!(!actual.join("a9c1e9700c0d").exists());
+    assert_eq!(3, ins.version_count());
+}